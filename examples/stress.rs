@@ -0,0 +1,991 @@
+//! A soak test of the concurrent APIs of `lazy_ref`.
+//!
+//! Every scenario races the threads on fresh cells in rounds, mixing the
+//! operations with a seeded random generator, and checks the invariants of
+//! the cells after every round. A failed invariant panics with the scenario,
+//! the round and the seed, which reproduce it with `--only` and `--seed`.
+//!
+//! The scenarios mixing the reads and the publications of references choose
+//! a publication with the probability set by `--writes`, in percent.
+//!
+//! ```text
+//! cargo run --release --example stress -- [--threads N] [--duration SECS]
+//!     [--seed N] [--writes PERCENT] [--only SCENARIO] [--list]
+//! ```
+//!
+//! To reproduce a race, copy a scenario and narrow it down to the operations
+//! of the report.
+
+use std::{
+    env,
+    num::NonZeroUsize,
+    panic, process, ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Barrier, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use lazy_ref::{
+    guarded_scope, lazy_graph::LazyGraph, snapshot_iter, ArenaAlloc, BroadcastRef,
+    ClaimableLazyRef, Error, FirstN, FutexLazyRef, HashConsing, KeyedLazyRef, LazyAnyRef, LazyFn,
+    LazyRef, LazyRefCache, LazyRefOr, LazyRefQueue, LazyRefSeq, LazyRefSliceExt, LazyResultRef,
+    LazyStrMap, LazyTypeMap, RankedLazyRef, RefInterner, RefRing, RetryPolicy, StampedLazyRef,
+    TypedAnyRef, WarmupSet,
+};
+
+const USAGE: &str = "usage: stress [--threads N] [--duration SECS] [--seed N] [--writes PERCENT] \
+                     [--only SCENARIO] [--list]";
+
+/// The number of the keys of the map-like containers.
+const KEYS: usize = 16;
+
+/// A scenario, run in rounds.
+type Scenario = fn(&Round);
+
+const SCENARIOS: &[(&str, Scenario)] = &[
+    ("lazy_ref", lazy_ref),
+    ("claimable", claimable),
+    ("futex", futex),
+    #[cfg(feature = "parking_lot")]
+    ("parking", parking),
+    ("broadcast", broadcast),
+    ("first_n", first_n),
+    ("queue", queue),
+    ("ranked", ranked),
+    ("ring", ring),
+    ("stamped", stamped),
+    ("warmup", warmup),
+    ("lazy_fn", lazy_fn),
+    ("seq", seq),
+    ("interner", interner),
+    ("type_map", type_map),
+    ("cache", cache),
+    ("keyed", keyed),
+    ("result_retry", result_retry),
+    ("graph", graph),
+    ("guarded", guarded),
+    ("or", or),
+    ("any", any),
+    ("str_map", str_map),
+    ("hash_cons", hash_cons),
+    ("set_all", set_all),
+    #[cfg(feature = "tokio")]
+    ("spawn_init", spawn_init),
+];
+
+struct Config {
+    threads: usize,
+    duration: Duration,
+    seed: u64,
+    writes: usize,
+    only: Option<String>,
+}
+
+impl Config {
+    fn from_args() -> Result<Self, String> {
+        let mut config = Self {
+            threads: thread::available_parallelism().map_or(4, |n| n.get().max(2)),
+            duration: Duration::from_secs(10),
+            seed: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |since| since.as_secs()),
+            writes: 50,
+            only: None,
+        };
+        let mut args = env::args().skip(1);
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("missing value of `{arg}`"))
+            };
+            match arg.as_str() {
+                "--threads" => config.threads = value()?.parse().map_err(|e| format!("{e}"))?,
+                "--duration" => {
+                    let secs: f64 = value()?.parse().map_err(|e| format!("{e}"))?;
+                    config.duration =
+                        Duration::try_from_secs_f64(secs).map_err(|e| format!("{e}"))?;
+                }
+                "--seed" => config.seed = value()?.parse().map_err(|e| format!("{e}"))?,
+                "--writes" => config.writes = value()?.parse().map_err(|e| format!("{e}"))?,
+                "--only" => config.only = Some(value()?),
+                "--list" => {
+                    for (name, _) in SCENARIOS {
+                        println!("{name}");
+                    }
+                    process::exit(0);
+                }
+                _ => return Err(format!("unknown argument `{arg}`")),
+            }
+        }
+        if config.threads < 2 {
+            return Err("at least 2 threads are needed".into());
+        }
+        if config.writes > 100 {
+            return Err("the writes are a percentage of the operations".into());
+        }
+        Ok(config)
+    }
+}
+
+fn main() {
+    let config = Config::from_args().unwrap_or_else(|e| {
+        eprintln!("{e}\n{USAGE}");
+        process::exit(2);
+    });
+    let scenarios: Vec<_> = SCENARIOS
+        .iter()
+        .filter(|(name, _)| config.only.as_ref().is_none_or(|only| only == name))
+        .collect();
+    if scenarios.is_empty() {
+        eprintln!("no scenario matches, see `--list`\n{USAGE}");
+        process::exit(2);
+    }
+    println!(
+        "{} threads, seed {}, {}% writes, {:?} in total",
+        config.threads, config.seed, config.writes, config.duration
+    );
+
+    let budget = config.duration / u32::try_from(scenarios.len()).unwrap_or(u32::MAX);
+    for (name, scenario) in scenarios {
+        let deadline = Instant::now() + budget;
+        let mut rounds = 0_u64;
+        while rounds == 0 || Instant::now() < deadline {
+            let round = Round {
+                threads: config.threads,
+                seed: config.seed.wrapping_add(rounds),
+                writes: config.writes,
+            };
+            if panic::catch_unwind(|| scenario(&round)).is_err() {
+                eprintln!(
+                    "{name}: round {rounds} failed, rerun with `--only {name} --seed {}`",
+                    round.seed
+                );
+                process::exit(1);
+            }
+            rounds += 1;
+        }
+        println!("{name}: {rounds} rounds");
+    }
+}
+
+/// The parameters of a round of a scenario.
+struct Round {
+    threads: usize,
+    seed: u64,
+    /// The percentage of the publications among the mixed operations.
+    writes: usize,
+}
+
+/// A xorshift generator mixing the operations of a thread.
+struct Rng {
+    state: u64,
+    writes: usize,
+}
+
+impl Rng {
+    fn new(round: &Round, thread: usize) -> Self {
+        Self {
+            state: (round.seed ^ (thread as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15)) | 1,
+            writes: round.writes,
+        }
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        usize::try_from(self.state % n as u64).unwrap_or(0)
+    }
+
+    /// Chooses whether the next operation publishes a reference rather than
+    /// reads one.
+    fn writes(&mut self) -> bool {
+        self.below(100) < self.writes
+    }
+}
+
+/// Runs `f` on the threads of `round` released at once, returning their
+/// results in the order of the threads.
+fn race<R: Send>(round: &Round, f: impl Fn(usize, &mut Rng) -> R + Sync) -> Vec<R> {
+    let barrier = Barrier::new(round.threads);
+    thread::scope(|s| {
+        let handles: Vec<_> = (0..round.threads)
+            .map(|thread| {
+                let (barrier, f) = (&barrier, &f);
+                s.spawn(move || {
+                    let mut rng = Rng::new(round, thread);
+                    barrier.wait();
+                    f(thread, &mut rng)
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    })
+}
+
+/// Asserts that all the threads have observed the same reference, returning
+/// it.
+fn assert_agreed<'a, T>(observed: &[&'a T]) -> &'a T {
+    let first = observed[0];
+    assert!(
+        observed.iter().all(|r| ptr::eq(*r, first)),
+        "the threads have observed different references"
+    );
+    first
+}
+
+/// Spins until `get` returns a reference.
+fn spin<'a, T: ?Sized>(get: impl Fn() -> Option<&'a T>) -> &'a T {
+    loop {
+        if let Some(r) = get() {
+            return r;
+        }
+        thread::yield_now();
+    }
+}
+
+fn lazy_ref(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = LazyRef::new();
+    let observed = race(round, |thread, rng| {
+        let value = &values[thread];
+        if !rng.writes() {
+            return cell.get().unwrap_or_else(|| cell.get_or_init(|| value));
+        }
+        match rng.below(3) {
+            0 => cell.get_or_init(|| value),
+            1 => {
+                cell.set_if_empty(value);
+                spin(|| cell.get())
+            }
+            _ => cell
+                .vacant()
+                .map_or_else(|| spin(|| cell.get()), |entry| entry.publish(value)),
+        }
+    });
+    let winner = assert_agreed(&observed);
+    assert!(ptr::eq(winner, cell.get().unwrap()));
+}
+
+fn claimable(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = ClaimableLazyRef::new();
+    let commits = AtomicUsize::new(0);
+    let observed = race(round, |thread, rng| {
+        if rng.writes() {
+            if let Some(guard) = cell.begin_init() {
+                guard.set_progress(0.5);
+                commits.fetch_add(1, Ordering::Relaxed);
+                return guard.commit(&values[thread]);
+            }
+        }
+        spin(|| {
+            cell.begin_init().map_or_else(
+                || cell.get(),
+                |guard| {
+                    commits.fetch_add(1, Ordering::Relaxed);
+                    Some(guard.commit(&values[thread]))
+                },
+            )
+        })
+    });
+    assert_agreed(&observed);
+    assert_eq!(
+        commits.into_inner(),
+        1,
+        "more than one initialization has committed"
+    );
+}
+
+fn futex(round: &Round) {
+    let value = 42;
+    let cell = FutexLazyRef::new();
+    let observed = race(round, |thread, rng| {
+        if thread == 0 {
+            for _ in 0..rng.below(1_000) {
+                std::hint::spin_loop();
+            }
+            cell.set(&value);
+            &value
+        } else if rng.below(2) == 0 {
+            cell.wait()
+        } else {
+            cell.wait_timeout(Duration::from_secs(30))
+                .expect("the publication has not woken the waiter")
+        }
+    });
+    assert!(ptr::eq(assert_agreed(&observed), &raw const value));
+}
+
+#[cfg(feature = "parking_lot")]
+fn parking(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = LazyRef::new();
+    let observed = race(round, |thread, rng| {
+        if thread == 0 || rng.writes() {
+            cell.get_or_init(|| &values[thread])
+        } else if rng.below(2) == 0 {
+            cell.wait()
+        } else {
+            cell.wait_timeout(Duration::from_secs(30))
+                .expect("the publication has not woken the waiter")
+        }
+    });
+    assert_agreed(&observed);
+}
+
+fn broadcast(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = BroadcastRef::new();
+    let published = AtomicUsize::new(0);
+    let observed = race(round, |thread, rng| {
+        let token = cell.subscribe();
+        if thread == 0 || rng.writes() {
+            match cell.set(&values[thread]) {
+                Ok(()) => {
+                    published.fetch_add(1, Ordering::Relaxed);
+                }
+                Err(e) => assert!(matches!(e, Error::AlreadyInitialized)),
+            }
+        }
+        token.wait()
+    });
+    assert_agreed(&observed);
+    assert_eq!(
+        published.into_inner(),
+        1,
+        "more than one publication has succeeded"
+    );
+}
+
+fn first_n(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let collector = FirstN::<'_, usize, 4>::new();
+    let accepted = race(round, |thread, _| collector.push(&values[thread]).ok());
+    let mut positions: Vec<usize> = accepted.iter().flatten().copied().collect();
+    positions.sort_unstable();
+    assert_eq!(positions, (0..round.threads.min(4)).collect::<Vec<_>>());
+    if round.threads >= 4 {
+        let winners = collector.as_slice().expect("all the slots are claimed");
+        for (thread, position) in accepted.iter().enumerate() {
+            if let Some(position) = position {
+                assert!(ptr::eq(winners[*position], &raw const values[thread]));
+            }
+        }
+    }
+}
+
+fn queue(round: &Round) {
+    let producers = round.threads / 2;
+    let values: Vec<usize> = (0..producers * KEYS).collect();
+    let queue = LazyRefQueue::<'_, usize, 4>::new();
+    let popped = AtomicUsize::new(0);
+    let observed = race(round, |thread, rng| {
+        let mut received = Vec::new();
+        if thread < producers {
+            for value in &values[thread * KEYS..(thread + 1) * KEYS] {
+                while queue.push(value).is_err() {
+                    thread::yield_now();
+                }
+            }
+        } else {
+            while popped.load(Ordering::Relaxed) < values.len() {
+                match queue.pop() {
+                    Some(value) => {
+                        popped.fetch_add(1, Ordering::Relaxed);
+                        received.push(value);
+                    }
+                    None if rng.below(2) == 0 => thread::yield_now(),
+                    None => {}
+                }
+            }
+        }
+        received
+    });
+    for received in &observed {
+        for pair in received.windows(2) {
+            assert!(
+                pair[0] / KEYS != pair[1] / KEYS || pair[0] < pair[1],
+                "the references of a producer have been reordered"
+            );
+        }
+    }
+    let mut received: Vec<&usize> = observed.into_iter().flatten().collect();
+    received.sort_unstable();
+    assert!(
+        received
+            .iter()
+            .zip(&values)
+            .all(|(r, value)| ptr::eq(*r, value)),
+        "a reference has been lost or duplicated"
+    );
+    assert_eq!(received.len(), values.len());
+    assert!(queue.is_empty());
+}
+
+fn ranked(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = RankedLazyRef::new();
+    let ranks = race(round, |thread, rng| {
+        let rank = u32::try_from(rng.below(4)).unwrap_or(0);
+        let _ = cell.get_or_init_preferring(rank, || &values[thread]);
+        rank
+    });
+    let highest = ranks.iter().copied().max();
+    assert_eq!(
+        cell.rank(),
+        highest,
+        "a lower rank has replaced a higher one"
+    );
+    let winner = *cell.get().unwrap();
+    assert_eq!(Some(ranks[winner]), highest);
+}
+
+fn ring(round: &Round) {
+    const PUBLICATIONS: usize = 256;
+    let values: Vec<usize> = (0..PUBLICATIONS).collect();
+    let ring = RefRing::<'_, usize, 4>::new();
+    race(round, |thread, rng| {
+        if thread == 0 {
+            for value in &values {
+                ring.publish(value);
+            }
+            return;
+        }
+        let mut latest = 0;
+        while latest + 1 < PUBLICATIONS {
+            let before = ring.latest().copied().unwrap_or(0);
+            assert!(before >= latest, "the latest reference has gone backwards");
+            let k = rng.below(4);
+            let nth = ring.nth_back(k).copied();
+            latest = ring.latest().copied().unwrap_or(0);
+            if let Some(nth) = nth {
+                assert!(
+                    (before..=latest).contains(&(nth + k)),
+                    "the history is inconsistent with the latest reference"
+                );
+            }
+        }
+    });
+    assert_eq!(ring.latest(), values.last());
+}
+
+fn stamped(round: &Round) {
+    const SETS: usize = 64;
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = StampedLazyRef::new();
+    let sets = race(round, |thread, rng| {
+        if thread % 2 == 0 {
+            for _ in 0..SETS {
+                cell.set(&values[thread]);
+            }
+            SETS
+        } else {
+            let mut last = 0;
+            for _ in 0..SETS {
+                if let Some((stamp, _)) = cell.get_stamped() {
+                    assert!(stamp >= last, "the stamp has gone backwards");
+                    last = stamp;
+                }
+                if rng.below(2) == 0 {
+                    thread::yield_now();
+                }
+            }
+            0
+        }
+    });
+    let (stamp, _) = cell.get_stamped().unwrap();
+    assert!(cell.validate(stamp));
+    assert_eq!(
+        stamp as usize,
+        sets.iter().sum::<usize>(),
+        "a publication is lost"
+    );
+}
+
+fn warmup(round: &Round) {
+    let values: Vec<usize> = (0..KEYS).collect();
+    let cells: Vec<LazyRef<'_, usize>> = (0..KEYS).map(|_| LazyRef::new()).collect();
+    let mut set = WarmupSet::new();
+    for cell in &cells {
+        set.register(cell);
+    }
+    race(round, |thread, rng| {
+        if thread == 0 {
+            set.wait_all_initialized();
+        } else {
+            for _ in 0..KEYS {
+                let key = rng.below(KEYS);
+                let _ = cells[key].get_or_init(|| &values[key]);
+                set.notify();
+            }
+            for (key, cell) in cells.iter().enumerate() {
+                let _ = cell.get_or_init(|| &values[key]);
+            }
+            set.notify();
+        }
+    });
+    assert!(set.is_ready());
+}
+
+/// The copies of the values of every key, handed out in turn, so that every
+/// computation of a key returns a distinct reference.
+struct Copies {
+    copies: Vec<Vec<usize>>,
+    next: AtomicUsize,
+}
+
+impl Copies {
+    fn new(threads: usize) -> Self {
+        Self {
+            copies: (0..threads).map(|_| (0..KEYS).collect()).collect(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    fn get(&self, key: usize) -> &usize {
+        let copy = self.next.fetch_add(1, Ordering::Relaxed) % self.copies.len();
+        &self.copies[copy][key]
+    }
+}
+
+/// Asserts that all the threads have observed the same reference for every
+/// key, and that `is_of` accepts it as the value of the key.
+fn assert_agreed_per_key<T: ?Sized>(
+    observed: &[Vec<(usize, &T)>],
+    is_of: impl Fn(usize, &T) -> bool,
+) {
+    let mut first: [Option<&T>; KEYS] = [None; KEYS];
+    for (key, r) in observed.iter().flatten() {
+        assert!(is_of(*key, r), "a key has got the value of another one");
+        let first = first[*key].get_or_insert(r);
+        assert!(
+            ptr::eq(*first, *r),
+            "the threads have observed different references"
+        );
+    }
+}
+
+/// Returns the values of the keys, for the static cells.
+const fn keys() -> [usize; KEYS] {
+    let mut values = [0; KEYS];
+    let mut key = 0;
+    while key < KEYS {
+        values[key] = key;
+        key += 1;
+    }
+    values
+}
+
+/// Checks whether `r` is a copy of the value of `key`.
+// The reference is what `assert_agreed_per_key` checks.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_copy_of(key: usize, r: &usize) -> bool {
+    *r == key
+}
+
+fn lazy_fn(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let f = LazyFn::new(|key: &usize| copies.get(*key));
+    let observed = race(round, |_, rng| {
+        (0..KEYS)
+            .map(|_| {
+                let key = rng.below(KEYS);
+                (key, f.call(key))
+            })
+            .collect()
+    });
+    assert_agreed_per_key(&observed, is_copy_of);
+    assert!(f.len() <= KEYS);
+}
+
+fn seq(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let seq = LazyRefSeq::new(KEYS, |key| copies.get(key));
+    let observed = race(round, |_, rng| {
+        if rng.below(4) == 0 {
+            let _ = seq.warm_for(Duration::from_micros(10));
+        }
+        (0..KEYS)
+            .map(|_| {
+                let key = rng.below(KEYS);
+                (key, seq.get(key).unwrap())
+            })
+            .collect()
+    });
+    assert_agreed_per_key(&observed, is_copy_of);
+}
+
+fn interner(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let interner = RefInterner::new();
+    let observed = race(round, |thread, rng| {
+        (0..KEYS)
+            .map(|_| {
+                let key = rng.below(KEYS);
+                (key, interner.intern(&copies.copies[thread][key]))
+            })
+            .collect()
+    });
+    assert_agreed_per_key(&observed, is_copy_of);
+    assert!(interner.len() <= KEYS);
+}
+
+fn type_map(round: &Round) {
+    let bytes: Vec<u8> = (0..=u8::MAX).collect();
+    let words: Vec<u16> = (0..=u16::from(u8::MAX)).collect();
+    let map = LazyTypeMap::new();
+    let observed = race(round, |thread, rng| {
+        let byte = map.get_or_init(|| &bytes[thread % bytes.len()]);
+        let word = match map.get::<u16>() {
+            Some(word) if !rng.writes() => word,
+            _ => map.get_or_init(|| &words[thread % words.len()]),
+        };
+        (byte, word)
+    });
+    let (observed_bytes, observed_words): (Vec<&u8>, Vec<&u16>) = observed.into_iter().unzip();
+    assert_agreed(&observed_bytes);
+    assert_agreed(&observed_words);
+    assert_eq!(map.len(), 2);
+}
+
+fn cache(round: &Round) {
+    let values: Vec<usize> = (0..KEYS).collect();
+    let cache: LazyRefCache<'_, usize, usize, 4> = LazyRefCache::new();
+    race(round, |_, rng| {
+        for _ in 0..KEYS {
+            let key = rng.below(KEYS);
+            assert_eq!(*cache.get_or_init(key, || &values[key]), key);
+            if let Some(r) = cache.get(&key) {
+                assert_eq!(*r, key, "a key has got the value of another one");
+            }
+        }
+    });
+}
+
+fn keyed(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let cell = KeyedLazyRef::new();
+    race(round, |_, rng| {
+        for _ in 0..KEYS {
+            let key = rng.below(2);
+            if rng.writes() {
+                assert_eq!(*cell.get_or_init_for(key, || copies.get(key)), key);
+            } else if let Some((stored, r)) = cell.get() {
+                assert_eq!(stored, *r, "the key and the reference have been torn apart");
+            }
+        }
+    });
+}
+
+fn result_retry(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let error = "unavailable";
+    let cell = LazyResultRef::<usize, &str>::with_retry_policy(RetryPolicy::Always);
+    let observed = race(round, |thread, rng| {
+        let mut ok: Option<&usize> = None;
+        for _ in 0..KEYS {
+            let fails = rng.below(2) == 0;
+            let r = cell.get_or_try_init(|| {
+                if fails {
+                    Err(&error)
+                } else {
+                    Ok(&values[thread])
+                }
+            });
+            match (ok, r) {
+                (Some(ok), r) => assert!(
+                    r.is_ok_and(|r| ptr::eq(r, ok)),
+                    "a published value has been replaced"
+                ),
+                (None, Ok(r)) => ok = Some(r),
+                (None, Err(_)) => {}
+            }
+        }
+        ok
+    });
+    let oks: Vec<&usize> = observed.into_iter().flatten().collect();
+    match cell.get() {
+        Some(Ok(winner)) => assert!(
+            oks.iter().all(|r| ptr::eq(*r, winner)),
+            "the threads have observed different references"
+        ),
+        _ => assert!(oks.is_empty(), "a published value has been replaced"),
+    }
+}
+
+fn graph(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let copies = &copies;
+    // Every node depends on its children in a binary tree rooted at 0.
+    let children = |key: usize| (2 * key + 1..(2 * key + 3).min(KEYS)).collect::<Vec<_>>();
+    let mut graph = LazyGraph::new();
+    let nodes: Vec<_> = (0..KEYS)
+        .map(|key| {
+            graph.add_node(move |deps: &[&usize]| {
+                assert!(
+                    deps.iter().map(|r| **r).eq(children(key)),
+                    "a node has got the references of other dependencies"
+                );
+                copies.get(key)
+            })
+        })
+        .collect();
+    for (key, node) in nodes.iter().enumerate() {
+        for child in children(key) {
+            graph.add_dependency(*node, nodes[child]);
+        }
+    }
+    let parallelism = NonZeroUsize::new(2).unwrap();
+    let observed = race(round, |_, rng| {
+        let node = nodes[rng.below(KEYS)];
+        let resolved = if rng.writes() {
+            graph.resolve_concurrent(node, parallelism)
+        } else {
+            graph.resolve(node)
+        };
+        resolved.expect("the graph has no cycles");
+        graph.resolve(nodes[0]).expect("the graph has no cycles");
+        nodes
+            .iter()
+            .enumerate()
+            .map(|(key, node)| {
+                (
+                    key,
+                    graph.get(*node).expect("the root depends on every node"),
+                )
+            })
+            .collect()
+    });
+    assert_agreed_per_key(&observed, is_copy_of);
+}
+
+fn guarded(round: &Round) {
+    static VALUES: [usize; KEYS] = keys();
+    let cell = LazyRef::new();
+    let observed = guarded_scope(|scope| {
+        let guarded = scope.guard(&cell);
+        let observed = race(round, |thread, rng| {
+            let guarded = guarded.clone();
+            let r = if thread == 0 || rng.writes() {
+                guarded.get_or_init(|| &VALUES[thread % KEYS])
+            } else {
+                spin(|| guarded.get())
+            };
+            ptr::from_ref(r).addr()
+        });
+        drop(guarded);
+        assert_eq!(scope.live(), 0, "a guard has outlived its thread");
+        observed
+    });
+    let winner = ptr::from_ref(cell.get().unwrap()).addr();
+    assert!(
+        observed.iter().all(|r| *r == winner),
+        "the threads have observed different references"
+    );
+}
+
+fn or(round: &Round) {
+    let fallback = usize::MAX;
+    let values: Vec<usize> = (0..round.threads).collect();
+    let cell = LazyRefOr::new(&fallback);
+    let observed = race(round, |thread, rng| {
+        let mut read = Vec::new();
+        for _ in 0..KEYS {
+            if thread == 0 || rng.writes() {
+                read.push(cell.get_or_init(|| &values[thread]));
+            } else {
+                read.push(cell.get_or_fallback());
+            }
+        }
+        read
+    });
+    let winner = cell.get().expect("the first thread initializes the cell");
+    assert!(
+        observed
+            .iter()
+            .flatten()
+            .all(|r| ptr::eq(*r, winner) || ptr::eq(*r, &raw const fallback)),
+        "the threads have observed different references"
+    );
+    assert!(ptr::eq(cell.get_or_fallback(), winner));
+}
+
+fn any(round: &Round) {
+    let values: Vec<usize> = (0..round.threads).collect();
+    let typed: Vec<TypedAnyRef<'_, usize>> = values.iter().map(TypedAnyRef::new).collect();
+    let cell = LazyAnyRef::new();
+    let observed = race(round, |thread, rng| {
+        if thread == 0 || rng.writes() {
+            let _ = cell.get_or_init(|| &typed[thread]);
+        }
+        let r = spin(|| cell.get_downcast::<usize>());
+        assert!(
+            cell.get_downcast::<u32>().is_none(),
+            "the reference has been downcast to another type"
+        );
+        r
+    });
+    assert_agreed(&observed);
+}
+
+/// A bounded arena of strings for [`str_map`], which panics once full.
+struct Strings {
+    slots: Vec<OnceLock<String>>,
+    next: AtomicUsize,
+}
+
+impl ArenaAlloc<String> for Strings {
+    fn alloc(&self, value: String) -> &String {
+        let slot = &self.slots[self.next.fetch_add(1, Ordering::Relaxed)];
+        slot.set(value).expect("the slots are handed out once");
+        slot.get().unwrap()
+    }
+}
+
+fn str_map(round: &Round) {
+    let keys: Vec<String> = (0..KEYS).map(|key| key.to_string()).collect();
+    // Every lookup allocates at most the key and the value.
+    let arena = Strings {
+        slots: (0..2 * KEYS * round.threads)
+            .map(|_| OnceLock::new())
+            .collect(),
+        next: AtomicUsize::new(0),
+    };
+    let map = LazyStrMap::new(&arena);
+    let observed = race(round, |_, rng| {
+        (0..KEYS)
+            .filter_map(|_| {
+                let key = rng.below(KEYS);
+                let value = if rng.writes() {
+                    map.get_or_init(&keys[key], str::to_owned)
+                } else {
+                    map.get(&keys[key])?
+                };
+                Some((key, value))
+            })
+            .collect()
+    });
+    assert_agreed_per_key(&observed, |key, value| value == keys[key]);
+    assert!(map.len() <= KEYS);
+}
+
+fn hash_cons(round: &Round) {
+    let copies = Copies::new(round.threads);
+    let store = HashConsing::new();
+    let observed = race(round, |thread, rng| {
+        (0..KEYS)
+            .filter_map(|_| {
+                let key = rng.below(KEYS);
+                let id = if rng.writes() {
+                    store.insert(&copies.copies[thread][key])
+                } else {
+                    store.id(&key)?
+                };
+                let r = store.resolve(id).expect("an identifier doesn't resolve");
+                Some((key, id, r))
+            })
+            .collect::<Vec<_>>()
+    });
+    let mut ids = [None; KEYS];
+    for (key, id, _) in observed.iter().flatten() {
+        assert_eq!(
+            *ids[*key].get_or_insert(*id),
+            *id,
+            "equal values have got different identifiers"
+        );
+    }
+    let distinct: Vec<_> = ids.iter().flatten().collect();
+    assert!(
+        distinct
+            .iter()
+            .all(|id| distinct.iter().filter(|other| *other == id).count() == 1),
+        "different values have got the same identifier"
+    );
+    let observed: Vec<Vec<_>> = observed
+        .into_iter()
+        .map(|resolved| resolved.into_iter().map(|(key, _, r)| (key, r)).collect())
+        .collect();
+    assert_agreed_per_key(&observed, is_copy_of);
+}
+
+fn set_all(round: &Round) {
+    let values: Vec<usize> = (0..KEYS).collect();
+    let refs: Vec<&usize> = values.iter().collect();
+    let cells: Vec<LazyRef<'_, usize>> = (0..KEYS).map(|_| LazyRef::new()).collect();
+    race(round, |thread, rng| {
+        if thread == 0 {
+            for _ in 0..rng.below(1_000) {
+                std::hint::spin_loop();
+            }
+            cells.set_all(&refs);
+            return;
+        }
+        let mut seen = [false; KEYS];
+        while !seen.iter().all(|seen| *seen) {
+            for (key, r) in snapshot_iter(&cells).enumerate() {
+                match r {
+                    Some(r) => {
+                        assert!(ptr::eq(r, refs[key]), "a cell has got another reference");
+                        seen[key] = true;
+                    }
+                    None => assert!(!seen[key], "a set cell has been observed empty"),
+                }
+            }
+            thread::yield_now();
+        }
+    });
+    assert_eq!(cells.count_initialized(), KEYS);
+}
+
+#[cfg(feature = "tokio")]
+fn spawn_init(round: &Round) {
+    use lazy_ref::warm;
+    use tokio::{runtime::Runtime, task::JoinSet};
+
+    /// The values published by the tasks.
+    static SPAWNED: [usize; KEYS] = keys();
+    /// The values published by the racing threads.
+    static DIRECT: [usize; KEYS] = keys();
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+
+    let runtime = RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .build()
+            .expect("the runtime is built")
+    });
+    // `spawn_init` takes static cells, so the cells of every round are leaked.
+    let cells: &'static [LazyRef<'static, usize>] =
+        Box::leak((0..KEYS).map(|_| LazyRef::new()).collect::<Box<[_]>>());
+    let observed = race(round, |thread, rng| {
+        if thread == 0 {
+            runtime.block_on(async {
+                let mut set = JoinSet::new();
+                for (key, cell) in cells.iter().enumerate() {
+                    let _ = warm::spawn_init(&mut set, cell, async move { &SPAWNED[key] });
+                }
+                set.join_all().await;
+            });
+        } else {
+            for _ in 0..KEYS {
+                let key = rng.below(KEYS);
+                if rng.writes() {
+                    let _ = cells[key].get_or_init(|| &DIRECT[key]);
+                }
+            }
+        }
+        cells
+            .iter()
+            .enumerate()
+            .map(|(key, cell)| (key, spin(|| cell.get())))
+            .collect()
+    });
+    assert_agreed_per_key(&observed, is_copy_of);
+}
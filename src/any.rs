@@ -0,0 +1,172 @@
+use core::{
+    any::Any,
+    fmt::{Debug, Formatter},
+};
+
+use crate::LazyRef;
+
+/// A type-erased reference to publish to a [`LazyAnyRef`].
+pub(crate) type Erased<'a> = &'a (dyn Any + Send + Sync);
+
+/// A reference remembering its type, to be published to a [`LazyAnyRef`].
+///
+/// The wrapper holds the type-erased reference that a [`LazyAnyRef`] points
+/// to, so it must outlive the cells it is published to.
+pub struct TypedAnyRef<'a, T> {
+    r: &'a T,
+    erased: Erased<'a>,
+}
+
+impl<T> Clone for TypedAnyRef<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedAnyRef<'_, T> {}
+
+impl<T: Debug> Debug for TypedAnyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("TypedAnyRef").field(self.r).finish()
+    }
+}
+
+impl<'a, T: Any + Send + Sync> TypedAnyRef<'a, T> {
+    /// Wraps `r`.
+    #[inline]
+    #[must_use]
+    pub const fn new(r: &'a T) -> Self {
+        Self { r, erased: r }
+    }
+
+    /// Gets the wrapped reference.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> &'a T {
+        self.r
+    }
+}
+
+/// A type-erased [`LazyRef`], initialized with a reference of any type and
+/// downcast back when read.
+///
+/// Since a reference to a trait object doesn't fit an atomic pointer, the
+/// cell points to the type-erased reference held by a [`TypedAnyRef`]
+/// instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyAnyRef, TypedAnyRef};
+///
+/// struct Plugin {
+///     name: &'static str,
+/// }
+///
+/// static PLUGIN: Plugin = Plugin { name: "audio" };
+/// static TYPED: TypedAnyRef<'static, Plugin> = TypedAnyRef::new(&PLUGIN);
+/// static SLOT: LazyAnyRef<'static> = LazyAnyRef::new();
+///
+/// assert!(SLOT.get_downcast::<Plugin>().is_none());
+/// SLOT.set(&TYPED);
+/// assert_eq!(SLOT.get_downcast::<Plugin>().map(|plugin| plugin.name), Some("audio"));
+/// assert!(SLOT.get_downcast::<u32>().is_none());
+/// ```
+pub struct LazyAnyRef<'a> {
+    inner: LazyRef<'a, Erased<'a>>,
+}
+
+impl Clone for LazyAnyRef<'_> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl Debug for LazyAnyRef<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("LazyAnyRef");
+        match self.get() {
+            Some(v) => d.field(&v.type_id()),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl Default for LazyAnyRef<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LazyAnyRef<'a> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+        }
+    }
+
+    /// Creates a new cell initialized with `r`.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized<T: Any + Send + Sync>(r: &'a TypedAnyRef<'a, T>) -> Self {
+        Self {
+            inner: LazyRef::new_initialized(&r.erased),
+        }
+    }
+
+    /// Gets the underlying type-erased reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a (dyn Any + Send + Sync)> {
+        self.inner.get().copied()
+    }
+
+    /// Gets the underlying reference if it is of type `T`.
+    ///
+    /// Returns `None` if the cell is empty or holds a reference of another
+    /// type.
+    #[inline]
+    #[must_use]
+    pub fn get_downcast<T: Any>(&self) -> Option<&'a T> {
+        self.get()?.downcast_ref()
+    }
+
+    /// Gets the underlying type-erased reference of the cell, initializing it
+    /// with `f` if the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init<T: Any + Send + Sync>(
+        &self,
+        f: impl FnOnce() -> &'a TypedAnyRef<'a, T>,
+    ) -> &'a (dyn Any + Send + Sync) {
+        *self.inner.get_or_init(|| &f().erased)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`.
+    #[inline]
+    pub fn set<T: Any + Send + Sync>(&self, r: &'a TypedAnyRef<'a, T>) {
+        self.inner.set(&r.erased);
+    }
+}
@@ -0,0 +1,30 @@
+/// An arena allocating values that live as long as the arena itself.
+///
+/// Used by [`LazyRef::get_or_clone_into`](crate::LazyRef::get_or_clone_into)
+/// to move values into long-lived storage.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{ArenaAlloc, LazyRef};
+///
+/// struct Leak;
+///
+/// impl<T> ArenaAlloc<T> for Leak {
+///     fn alloc(&self, value: T) -> &T {
+///         Box::leak(Box::new(value))
+///     }
+/// }
+///
+/// let arena = Leak;
+/// let lazy_ref = LazyRef::new();
+///
+/// let temporary = String::from("lazy");
+/// let r = lazy_ref.get_or_clone_into(&arena, &temporary);
+/// drop(temporary);
+/// assert_eq!(r, "lazy");
+/// ```
+pub trait ArenaAlloc<T> {
+    /// Moves `value` into the arena, returning the reference to it.
+    fn alloc(&self, value: T) -> &T;
+}
@@ -0,0 +1,190 @@
+use core::hint::spin_loop;
+
+/// A strategy of waiting between the attempts of a spin loop.
+///
+/// Every wait starts with a fresh strategy, which is asked to
+/// [`snooze`](Self::snooze) after every failed attempt.
+pub trait Backoff {
+    /// Waits before the next attempt.
+    fn snooze(&mut self);
+}
+
+impl<B: Backoff + ?Sized> Backoff for &mut B {
+    #[inline]
+    fn snooze(&mut self) {
+        (**self).snooze();
+    }
+}
+
+impl Backoff for crossbeam_utils::Backoff {
+    #[inline]
+    fn snooze(&mut self) {
+        crossbeam_utils::Backoff::snooze(self);
+    }
+}
+
+/// An exponential [`Backoff`] with configurable limits.
+///
+/// The `n`-th snooze spins `2^n` times while `n` doesn't exceed the spin
+/// limit. Later snoozes yield the current thread with the `std` feature, and
+/// keep spinning `2^spin_limit` times without it. The yield limit caps the
+/// growth of `n`, after which [`is_completed`](Self::is_completed) suggests
+/// blocking instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{Backoff, SpinBackoff};
+///
+/// let mut backoff = SpinBackoff::new(2, 4);
+/// for _ in 0..5 {
+///     assert!(!backoff.is_completed());
+///     backoff.snooze();
+/// }
+/// assert!(backoff.is_completed());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SpinBackoff {
+    spin_limit: u32,
+    yield_limit: u32,
+    step: u32,
+}
+
+impl Default for SpinBackoff {
+    /// Creates a new backoff with the limits of [`crossbeam_utils::Backoff`].
+    #[inline]
+    fn default() -> Self {
+        Self::new(6, 10)
+    }
+}
+
+impl SpinBackoff {
+    /// Creates a new backoff with the given limits.
+    ///
+    /// The spin limit is capped at 31.
+    #[inline]
+    #[must_use]
+    pub const fn new(spin_limit: u32, yield_limit: u32) -> Self {
+        Self {
+            spin_limit: if spin_limit < 31 { spin_limit } else { 31 },
+            yield_limit,
+            step: 0,
+        }
+    }
+
+    /// Returns the spin limit.
+    #[inline]
+    #[must_use]
+    pub fn spin_limit(&self) -> u32 {
+        self.spin_limit
+    }
+
+    /// Returns the yield limit.
+    #[inline]
+    #[must_use]
+    pub fn yield_limit(&self) -> u32 {
+        self.yield_limit
+    }
+
+    /// Checks whether the yield limit is exceeded, so that blocking is
+    /// preferable to snoozing.
+    #[inline]
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.step > self.yield_limit
+    }
+
+    /// Restarts the backoff.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+}
+
+impl Backoff for SpinBackoff {
+    #[inline]
+    fn snooze(&mut self) {
+        if self.step <= self.spin_limit {
+            (0..1_u32 << self.step).for_each(|_| spin_loop());
+        } else {
+            #[cfg(feature = "std")]
+            std::thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            (0..1_u32 << self.spin_limit).for_each(|_| spin_loop());
+        }
+        if self.step <= self.yield_limit {
+            self.step += 1;
+        }
+    }
+}
+
+/// An exponential [`Backoff`] with the limits of [`SpinBackoff`] fixed at
+/// compile time.
+///
+/// The limits take no storage and need no branching on configuration, which
+/// suits the embedded targets whose tuning is known ahead. The spin limit is
+/// capped at 31.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{ConstBackoff, FirstN};
+///
+/// let values = [1, 2];
+/// let first = FirstN::<'_, i32, 2>::new();
+/// for value in &values {
+///     let _ = first.push(value);
+/// }
+/// let collected = first.wait_full_with(ConstBackoff::<4, 8>::new());
+/// assert_eq!(collected, [&1, &2]);
+/// assert_eq!(size_of::<ConstBackoff<4, 8>>(), size_of::<u32>());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ConstBackoff<const SPIN: u32, const YIELD: u32> {
+    step: u32,
+}
+
+impl<const SPIN: u32, const YIELD: u32> ConstBackoff<SPIN, YIELD> {
+    /// The spin limit, capped at 31.
+    pub const SPIN_LIMIT: u32 = if SPIN < 31 { SPIN } else { 31 };
+    /// The yield limit.
+    pub const YIELD_LIMIT: u32 = YIELD;
+
+    /// Creates a new backoff.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { step: 0 }
+    }
+
+    /// Checks whether the yield limit is exceeded, so that blocking is
+    /// preferable to snoozing.
+    #[inline]
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.step > YIELD
+    }
+
+    /// Restarts the backoff.
+    #[inline]
+    pub fn reset(&mut self) {
+        self.step = 0;
+    }
+}
+
+impl<const SPIN: u32, const YIELD: u32> Backoff for ConstBackoff<SPIN, YIELD> {
+    #[inline]
+    fn snooze(&mut self) {
+        if self.step <= Self::SPIN_LIMIT {
+            (0..1_u32 << self.step).for_each(|_| spin_loop());
+        } else {
+            #[cfg(feature = "std")]
+            std::thread::yield_now();
+            #[cfg(not(feature = "std"))]
+            (0..1_u32 << Self::SPIN_LIMIT).for_each(|_| spin_loop());
+        }
+        if self.step <= YIELD {
+            self.step += 1;
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Condvar, Mutex, MutexGuard, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{Error, LazyRef};
+
+/// A [`LazyRef`] published once to all of its registered readers.
+///
+/// Readers register with [`subscribe`](Self::subscribe), getting a
+/// [`BroadcastToken`] to poll or wait for the publication with, instead of
+/// polling the cell in a loop. Reading the published reference costs the same
+/// as reading a [`LazyRef`].
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::BroadcastRef;
+///
+/// let config = "debug".to_string();
+/// let cell = BroadcastRef::new();
+///
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         let token = cell.subscribe();
+///         s.spawn(move || assert_eq!(token.wait(), "debug"));
+///     }
+///     assert!(cell.set(&config).is_ok());
+///     assert!(cell.set(&config).is_err());
+/// });
+/// assert_eq!(cell.subscribers(), 0);
+/// ```
+pub struct BroadcastRef<'a, T> {
+    inner: LazyRef<'a, T>,
+    subscribers: AtomicUsize,
+    lock: Mutex<()>,
+    published: Condvar,
+}
+
+impl<T: Debug> Debug for BroadcastRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_tuple("BroadcastRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for BroadcastRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> BroadcastRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+            subscribers: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            published: Condvar::new(),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is not published yet.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Checks whether the cell is published.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Returns the number of the live tokens.
+    #[inline]
+    #[must_use]
+    pub fn subscribers(&self) -> usize {
+        self.subscribers.load(Ordering::Relaxed)
+    }
+
+    /// Registers a new reader, returning its token.
+    #[inline]
+    pub fn subscribe(&self) -> BroadcastToken<'_, 'a, T> {
+        self.subscribers.fetch_add(1, Ordering::Relaxed);
+        BroadcastToken { cell: self }
+    }
+
+    /// Publishes `r`, waking all the readers waiting for it.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AlreadyInitialized`] if the cell is already published,
+    /// in which case it is left as is.
+    #[inline]
+    pub fn set(&self, r: &'a T) -> Result<(), Error> {
+        let entry = self.inner.vacant().ok_or(Error::AlreadyInitialized)?;
+        if !core::ptr::eq(entry.publish(r), r) {
+            return Err(Error::AlreadyInitialized);
+        }
+        // Taking the lock ensures that the waiters either see the reference or are
+        // already waiting to be notified.
+        drop(self.lock());
+        self.published.notify_all();
+        Ok(())
+    }
+
+    /// Locks the waiters, ignoring poisoning.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, ()> {
+        self.lock.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A registered reader of a [`BroadcastRef`].
+///
+/// Created by [`BroadcastRef::subscribe`].
+pub struct BroadcastToken<'b, 'a, T> {
+    cell: &'b BroadcastRef<'a, T>,
+}
+
+impl<T: Debug> Debug for BroadcastToken<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("BroadcastToken").field(self.cell).finish()
+    }
+}
+
+impl<T> Drop for BroadcastToken<'_, '_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.subscribers.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl<'a, T> BroadcastToken<'_, 'a, T> {
+    /// Gets the published reference.
+    ///
+    /// Returns `None` if the cell is not published yet.
+    #[inline]
+    #[must_use]
+    pub fn try_get(&self) -> Option<&'a T> {
+        self.cell.get()
+    }
+
+    /// Checks whether the cell is published.
+    #[inline]
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.cell.is_initialized()
+    }
+
+    /// Blocks the current thread until the cell is published, returning the
+    /// published reference.
+    #[inline]
+    pub fn wait(&self) -> &'a T {
+        if let Some(r) = self.try_get() {
+            return r;
+        }
+        let mut guard = self.cell.lock();
+        loop {
+            if let Some(r) = self.try_get() {
+                return r;
+            }
+            guard = self
+                .cell
+                .published
+                .wait(guard)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Blocks the current thread until the cell is published or `timeout`
+    /// elapses, returning the published reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if `timeout` elapses first.
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<&'a T, Error> {
+        if let Some(r) = self.try_get() {
+            return Ok(r);
+        }
+        let deadline = Instant::now() + timeout;
+        let mut guard = self.cell.lock();
+        loop {
+            if let Some(r) = self.try_get() {
+                return Ok(r);
+            }
+            let left = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(Error::Timeout)?;
+            guard = self
+                .cell
+                .published
+                .wait_timeout(guard, left)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+}
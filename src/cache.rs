@@ -0,0 +1,128 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{telemetry::SlowPath, KeyedLazyRef};
+
+/// A non-blocking, fixed-size memo cache of immutable references with `WAYS`
+/// entries.
+///
+/// When the cache is full, the entry to be replaced is chosen with the CLOCK
+/// algorithm, an approximation of LRU replacement: every lookup hit marks its
+/// entry as recently used, and recently used entries get a second chance
+/// before being replaced.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyRefCache;
+///
+/// let squares: Vec<usize> = (0..10).map(|x| x * x).collect();
+/// let cache: LazyRefCache<'_, usize, usize, 2> = LazyRefCache::new();
+///
+/// assert_eq!(cache.get_or_init(2, || &squares[2]), &4);
+/// assert_eq!(cache.get_or_init(3, || &squares[3]), &9);
+/// assert_eq!(cache.get(&2), Some(&4));
+///
+/// // Replaces the entry of `3` since `2` has been recently used.
+/// assert_eq!(cache.get_or_init(4, || &squares[4]), &16);
+/// assert_eq!(cache.get(&2), Some(&4));
+/// assert_eq!(cache.get(&3), None);
+/// ```
+pub struct LazyRefCache<'a, K, T, const WAYS: usize> {
+    slots: [KeyedLazyRef<'a, K, T>; WAYS],
+    referenced: [AtomicBool; WAYS],
+    hand: AtomicUsize,
+}
+
+impl<K: Copy + Debug, T: Debug, const WAYS: usize> Debug for LazyRefCache<'_, K, T, WAYS> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.entries()).finish()
+    }
+}
+
+impl<K, T, const WAYS: usize> Default for LazyRefCache<'_, K, T, WAYS> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, T, const WAYS: usize> LazyRefCache<'_, K, T, WAYS> {
+    /// Creates a new empty cache.
+    ///
+    /// Fails to compile if `WAYS` is zero.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        const { assert!(WAYS > 0, "`LazyRefCache` must have at least one way") };
+        Self {
+            slots: [const { KeyedLazyRef::new() }; WAYS],
+            referenced: [const { AtomicBool::new(false) }; WAYS],
+            hand: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<'a, K: Copy + Eq, T, const WAYS: usize> LazyRefCache<'a, K, T, WAYS> {
+    /// Gets the reference computed for `key`.
+    ///
+    /// Returns `None` if the cache holds no entry for `key`.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, key: &K) -> Option<&'a T> {
+        self.slots
+            .iter()
+            .zip(&self.referenced)
+            .find_map(|(slot, referenced)| {
+                let r = slot.get_for(key)?;
+                if !referenced.load(Ordering::Relaxed) {
+                    referenced.store(true, Ordering::Relaxed);
+                }
+                Some(r)
+            })
+    }
+
+    /// Gets the reference computed for `key`, inserting the reference
+    /// returned by `f` if the cache holds no entry for `key`.
+    ///
+    /// Many threads may call `get_or_init` concurrently. In this case
+    /// multiple functions can be executed.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, key: K, f: impl FnOnce() -> &'a T) -> &'a T {
+        self.get(&key).unwrap_or_else(|| {
+            let mut slow = SlowPath::new("LazyRefCache");
+            let r = slow.compute(f);
+            self.insert(key, r);
+            slow.finish(r)
+        })
+    }
+
+    /// Inserts `r` computed for `key`, replacing an empty or not recently
+    /// used entry.
+    #[inline]
+    pub fn insert(&self, key: K, r: &'a T) {
+        let mut victim = self.hand.fetch_add(1, Ordering::Relaxed) % WAYS;
+        for _ in 0..2 * WAYS {
+            if !self.slots[victim].is_initialized()
+                || !self.referenced[victim].swap(false, Ordering::Relaxed)
+            {
+                break;
+            }
+            victim = self.hand.fetch_add(1, Ordering::Relaxed) % WAYS;
+        }
+        self.referenced[victim].store(false, Ordering::Relaxed);
+        self.slots[victim].set(key, r);
+    }
+}
+
+impl<'a, K: Copy, T, const WAYS: usize> LazyRefCache<'a, K, T, WAYS> {
+    /// Iterates over the keys and the references of the cached entries.
+    #[inline]
+    pub(crate) fn entries(&self) -> impl Iterator<Item = (K, &'a T)> + '_ {
+        self.slots.iter().filter_map(KeyedLazyRef::get)
+    }
+}
@@ -0,0 +1,240 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use crate::LazyRef;
+
+/// The initialization state of a [`ClaimableLazyRef`].
+///
+/// The states only advance from [`Empty`](Self::Empty) to
+/// [`Claimed`](Self::Claimed) and then either to
+/// [`Initialized`](Self::Initialized) or back to [`Empty`](Self::Empty), which
+/// allows to build custom wait strategies, such as spinning or parking, on top
+/// of [`ClaimableLazyRef::state`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum InitState {
+    /// The cell is empty and can be claimed.
+    Empty,
+    /// An initialization is in progress.
+    Claimed,
+    /// The cell is initialized.
+    Initialized,
+}
+
+/// A [`LazyRef`] initialized with a two-phase protocol, so that only one
+/// initializer runs at a time.
+///
+/// [`begin_init`](Self::begin_init) hands out an [`InitGuard`] to a single
+/// thread at a time, while the others get `None` instead of waiting. The
+/// guard publishes the reference with [`InitGuard::commit`], and dropping it
+/// without a commit aborts the initialization, so that it can be begun again.
+/// Reading the cell costs the same as reading a [`LazyRef`].
+///
+/// A long-running initializer can report its progress with
+/// [`InitGuard::set_progress`] for the others to observe through
+/// [`progress`](Self::progress).
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::ClaimableLazyRef;
+///
+/// let value = 42;
+/// let cell = ClaimableLazyRef::new();
+///
+/// let guard = cell.begin_init().unwrap();
+/// assert!(cell.begin_init().is_none());
+/// drop(guard);
+///
+/// let guard = cell.begin_init().unwrap();
+/// assert_eq!(guard.commit(&value), &42);
+/// assert!(cell.begin_init().is_none());
+/// assert_eq!(cell.get(), Some(&42));
+///
+/// let cell = ClaimableLazyRef::new();
+/// let guard = cell.begin_init().unwrap();
+/// assert_eq!(cell.progress(), Some(0.0));
+/// guard.set_progress(0.5);
+/// assert_eq!(cell.progress(), Some(0.5));
+/// let _ = guard.commit(&value);
+/// assert_eq!(cell.progress(), None);
+/// ```
+///
+/// Waiting for an initialization in progress:
+///
+/// ```rust
+/// use lazy_ref::{ClaimableLazyRef, InitState};
+///
+/// let value = 42;
+/// let cell = ClaimableLazyRef::new();
+///
+/// std::thread::scope(|s| {
+///     let guard = cell.begin_init().unwrap();
+///     let value = &value;
+///     s.spawn(move || guard.commit(value));
+///     while cell.state() == InitState::Claimed {
+///         std::thread::yield_now();
+///     }
+/// });
+/// assert_eq!(cell.state(), InitState::Initialized);
+/// ```
+pub struct ClaimableLazyRef<'a, T> {
+    inner: LazyRef<'a, T>,
+    /// Whether an initialization is in progress or has been committed.
+    claimed: AtomicBool,
+    /// The bits of the progress of the initialization in progress.
+    progress: AtomicU32,
+}
+
+impl<T: Debug> Debug for ClaimableLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("ClaimableLazyRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None if self.claimed.load(Ordering::Relaxed) => {
+                d.field(&format_args!("<initializing>"))
+            }
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for ClaimableLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> ClaimableLazyRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+            claimed: AtomicBool::new(false),
+            progress: AtomicU32::new(0),
+        }
+    }
+
+    /// Creates a new initialized cell.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized(r: &'a T) -> Self {
+        Self {
+            inner: LazyRef::new_initialized(r),
+            claimed: AtomicBool::new(true),
+            progress: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Returns the initialization state of the cell.
+    #[inline]
+    #[must_use]
+    pub fn state(&self) -> InitState {
+        if self.is_initialized() {
+            InitState::Initialized
+        } else if self.claimed.load(Ordering::Acquire) {
+            // The claim may have been committed since the first check.
+            if self.is_initialized() {
+                InitState::Initialized
+            } else {
+                InitState::Claimed
+            }
+        } else {
+            InitState::Empty
+        }
+    }
+
+    /// Returns the progress last reported by the initialization in
+    /// progress, from `0.0` to `1.0`.
+    ///
+    /// Returns `None` if no initialization is in progress.
+    #[inline]
+    #[must_use]
+    pub fn progress(&self) -> Option<f32> {
+        let progress = f32::from_bits(self.progress.load(Ordering::Relaxed));
+        (self.claimed.load(Ordering::Relaxed) && !self.is_initialized()).then_some(progress)
+    }
+
+    /// Begins the initialization of the cell.
+    ///
+    /// Returns `None` if the cell is initialized, or if another
+    /// initialization is in progress.
+    #[inline]
+    #[must_use]
+    pub fn begin_init(&self) -> Option<InitGuard<'_, 'a, T>> {
+        self.claimed
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .ok()?;
+        self.progress.store(0.0_f32.to_bits(), Ordering::Relaxed);
+        Some(InitGuard { cell: self })
+    }
+}
+
+/// An exclusive right to initialize a [`ClaimableLazyRef`].
+///
+/// Created by [`ClaimableLazyRef::begin_init`]. Dropping the guard without a
+/// [`commit`](Self::commit) aborts the initialization.
+#[must_use = "dropping the guard aborts the initialization"]
+pub struct InitGuard<'c, 'a, T> {
+    cell: &'c ClaimableLazyRef<'a, T>,
+}
+
+impl<T> Debug for InitGuard<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("InitGuard").finish_non_exhaustive()
+    }
+}
+
+impl<T> Drop for InitGuard<'_, '_, T> {
+    #[inline]
+    fn drop(&mut self) {
+        self.cell.claimed.store(false, Ordering::Release);
+    }
+}
+
+impl<'a, T> InitGuard<'_, 'a, T> {
+    /// Reports the progress of the initialization, clamped from `0.0` to
+    /// `1.0`, with `NaN` reported as `0.0`.
+    #[inline]
+    pub fn set_progress(&self, progress: f32) {
+        let progress = if progress.is_nan() {
+            0.0
+        } else {
+            progress.clamp(0.0, 1.0)
+        };
+        self.cell
+            .progress
+            .store(progress.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Publishes `r`, completing the initialization.
+    #[inline]
+    pub fn commit(self, r: &'a T) -> &'a T {
+        self.cell.inner.set(r);
+        core::mem::forget(self);
+        r
+    }
+}
@@ -0,0 +1,229 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use std::hash::{BuildHasher, Hash};
+
+#[cfg(feature = "std")]
+use crate::LazyFn;
+#[cfg(feature = "alloc")]
+use crate::{Handle, HandleMap, LazyRefSeq, SmallLazyRefVec};
+use crate::{LazyRef, LazyRefCache};
+
+/// A container of lazy-initialized immutable references keyed by `K`.
+///
+/// Implemented by the slices, arrays and vectors of [`LazyRef`] cells keyed
+/// by their indices, [`SmallLazyRefVec`], [`LazyRefSeq`], [`HandleMap`],
+/// [`LazyRefCache`] and [`LazyFn`], so that the code initializing or
+/// inspecting them can be written once.
+///
+/// The containers computing their entries with a stored function, such as
+/// [`LazyRefSeq`] and [`LazyFn`], don't call it from [`get`](Self::get),
+/// and initialize the missing entries with the function given to
+/// [`get_or_init`](Self::get_or_init) instead.
+///
+/// The methods are shadowed by the inherent methods of the same names, so
+/// they are meant to be called through generic code or with the fully
+/// qualified syntax.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyContainer, LazyRef, SmallLazyRefVec};
+///
+/// fn warm_up<'a, C: LazyContainer<'a, usize, u64>>(cells: &C, values: &'a [u64]) -> u64 {
+///     for (idx, value) in values.iter().enumerate() {
+///         let _ = cells.get_or_init(idx, || value);
+///     }
+///     cells.iter_initialized().map(|(_, value)| value).sum()
+/// }
+///
+/// let values = [1, 2, 3];
+///
+/// let array: [LazyRef<'_, u64>; 2] = LazyRef::new_uninit_array();
+/// assert_eq!(warm_up(&array, &values), 3);
+///
+/// let mut vec = SmallLazyRefVec::<'_, u64, 4>::new();
+/// for _ in 0..3 {
+///     vec.push();
+/// }
+/// assert_eq!(warm_up(&vec, &values), 6);
+/// assert_eq!(LazyContainer::get(&vec, 2), Some(&3));
+/// ```
+pub trait LazyContainer<'a, K, T: 'a> {
+    /// Gets the reference of the entry of `key`.
+    ///
+    /// Returns `None` if the entry is empty or there is none.
+    #[must_use]
+    fn get(&self, key: K) -> Option<&'a T>;
+
+    /// Gets the reference of the entry of `key`, initializing it with `f` if
+    /// the entry is empty.
+    ///
+    /// Returns `None` if the container has no entry for `key`, such as for
+    /// an index out of bounds or a stale handle.
+    #[must_use]
+    fn get_or_init(&self, key: K, f: impl FnOnce() -> &'a T) -> Option<&'a T>;
+
+    /// Iterates over the keys and the references of the initialized entries.
+    fn iter_initialized(&self) -> impl Iterator<Item = (K, &'a T)> + '_;
+}
+
+/// Iterates over the indices and the references of the initialized cells.
+#[inline]
+fn iter_indexed<'s, 'a, T>(
+    cells: &'s [LazyRef<'a, T>],
+) -> impl Iterator<Item = (usize, &'a T)> + 's {
+    cells
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, cell)| Some((idx, cell.get()?)))
+}
+
+impl<'a, T> LazyContainer<'a, usize, T> for [LazyRef<'a, T>] {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&'a T> {
+        self.get(key)?.get()
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: usize, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.get(key)?.get_or_init(f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        iter_indexed(self)
+    }
+}
+
+impl<'a, T, const N: usize> LazyContainer<'a, usize, T> for [LazyRef<'a, T>; N] {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&'a T> {
+        self.as_slice().get(key)?.get()
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: usize, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.as_slice().get(key)?.get_or_init(f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        iter_indexed(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> LazyContainer<'a, usize, T> for Vec<LazyRef<'a, T>> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&'a T> {
+        self.as_slice().get(key)?.get()
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: usize, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.as_slice().get(key)?.get_or_init(f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        iter_indexed(self)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, const INLINE: usize> LazyContainer<'a, usize, T> for SmallLazyRefVec<'a, T, INLINE> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&'a T> {
+        self.as_slice().get(key)?.get()
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: usize, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.as_slice().get(key)?.get_or_init(f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        iter_indexed(self.as_slice())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T, F> LazyContainer<'a, usize, T> for LazyRefSeq<'a, T, F> {
+    #[inline]
+    fn get(&self, key: usize) -> Option<&'a T> {
+        self.peek(key)
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: usize, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.cells().get(key)?.get_or_init(f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        iter_indexed(self.cells())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> LazyContainer<'a, Handle, T> for HandleMap<'a, T> {
+    #[inline]
+    fn get(&self, key: Handle) -> Option<&'a T> {
+        self.get(key)
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: Handle, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        self.get_or_init(key, f)
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (Handle, &'a T)> + '_ {
+        self.iter()
+            .filter_map(|(handle, cell)| Some((handle, cell.get()?)))
+    }
+}
+
+impl<'a, K: Copy + Eq, T, const WAYS: usize> LazyContainer<'a, K, T>
+    for LazyRefCache<'a, K, T, WAYS>
+{
+    #[inline]
+    fn get(&self, key: K) -> Option<&'a T> {
+        self.get(&key)
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: K, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.get_or_init(key, f))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (K, &'a T)> + '_ {
+        self.entries()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, A, T, F, S> LazyContainer<'a, A, T> for LazyFn<'a, A, T, F, S>
+where
+    A: Hash + Eq + Clone,
+    F: Fn(&A) -> &'a T,
+    S: BuildHasher,
+{
+    #[inline]
+    fn get(&self, key: A) -> Option<&'a T> {
+        self.get(&key)
+    }
+
+    #[inline]
+    fn get_or_init(&self, key: A, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        Some(self.memoize(key, |_| f()))
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> impl Iterator<Item = (A, &'a T)> + '_ {
+        self.entries().into_iter().map(|(arg, r)| (arg.clone(), r))
+    }
+}
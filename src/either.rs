@@ -0,0 +1,160 @@
+use core::fmt::{Debug, Formatter};
+
+use either::Either;
+
+use crate::tagged::{LazyTagged, Tagged};
+
+/// A non-blocking synchronization primitive (cell) for lazy-initialized
+/// immutable references to one of two types.
+///
+/// The same as [`LazyRef`](crate::LazyRef), but holds
+/// [`Either`]`<&'a L, &'a R>`, storing the variant in the lowest bit of the
+/// pointer. Therefore, both `L` and `R` must be aligned to at least two bytes,
+/// which is checked at compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// use either::Either;
+/// use lazy_ref::LazyEitherRef;
+///
+/// let fast = 1_u32;
+/// let slow = "slow".to_string();
+///
+/// let backend = LazyEitherRef::new();
+/// assert_eq!(backend.get_or_init(|| Either::Right(&slow)), Either::Right(&slow));
+/// assert_eq!(backend.get_or_init(|| Either::Left(&fast)), Either::Right(&slow));
+/// ```
+///
+/// Types aligned to one byte are rejected:
+///
+/// ```compile_fail
+/// use lazy_ref::LazyEitherRef;
+///
+/// let _ = LazyEitherRef::<u8, u32>::new();
+/// ```
+pub struct LazyEitherRef<'a, L, R> {
+    inner: LazyTagged<'a, L, R>,
+}
+
+impl<L, R> Clone for LazyEitherRef<'_, L, R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.get().map(Self::new_initialized).unwrap_or_default()
+    }
+}
+
+impl<L: PartialEq, R: PartialEq> PartialEq for LazyEitherRef<'_, L, R> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<L: Eq, R: Eq> Eq for LazyEitherRef<'_, L, R> {}
+
+impl<L: Debug, R: Debug> Debug for LazyEitherRef<'_, L, R> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("LazyEitherRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<L, R> Default for LazyEitherRef<'_, L, R> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, L, R> From<Either<&'a L, &'a R>> for LazyEitherRef<'a, L, R> {
+    #[inline]
+    fn from(r: Either<&'a L, &'a R>) -> Self {
+        Self::new_initialized(r)
+    }
+}
+
+impl<'a, L, R> From<Tagged<'a, L, R>> for Either<&'a L, &'a R> {
+    #[inline]
+    fn from(r: Tagged<'a, L, R>) -> Self {
+        match r {
+            Tagged::Left(l) => Either::Left(l),
+            Tagged::Right(r) => Either::Right(r),
+        }
+    }
+}
+
+impl<'a, L, R> From<Either<&'a L, &'a R>> for Tagged<'a, L, R> {
+    #[inline]
+    fn from(r: Either<&'a L, &'a R>) -> Self {
+        match r {
+            Either::Left(l) => Tagged::Left(l),
+            Either::Right(r) => Tagged::Right(r),
+        }
+    }
+}
+
+impl<'a, L, R> LazyEitherRef<'a, L, R> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyTagged::new(),
+        }
+    }
+
+    /// Creates a new initialized cell.
+    #[inline]
+    #[must_use]
+    pub fn new_initialized(r: Either<&'a L, &'a R>) -> Self {
+        Self {
+            inner: LazyTagged::new_initialized(r.into()),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<Either<&'a L, &'a R>> {
+        self.inner.get().map(Into::into)
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`](crate::LazyRef::get_or_init).
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> Either<&'a L, &'a R>) -> Either<&'a L, &'a R> {
+        self.inner.get_or_init(|| f().into()).into()
+    }
+
+    /// Consumes the `LazyEitherRef`, returning the wrapped reference.
+    /// Returns `None` if the cell was empty.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<Either<&'a L, &'a R>> {
+        self.inner.into_inner().map(Into::into)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`.
+    #[inline]
+    pub fn set(&self, r: Either<&'a L, &'a R>) {
+        self.inner.set(r.into());
+    }
+}
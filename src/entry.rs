@@ -0,0 +1,32 @@
+use core::fmt::{Debug, Formatter};
+
+use crate::LazyRef;
+
+/// A view into an empty [`LazyRef`].
+///
+/// Created by [`LazyRef::vacant`]. The entry is the only way to fill the cell
+/// it was created for, and can be dropped to abandon it. Unlike
+/// [`ClaimableLazyRef`](crate::ClaimableLazyRef), holding an entry doesn't
+/// prevent other threads from taking entries of their own.
+#[must_use = "dropping the entry abandons it"]
+pub struct VacantEntry<'c, 'a, T> {
+    pub(crate) cell: &'c LazyRef<'a, T>,
+}
+
+impl<T> Debug for VacantEntry<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("VacantEntry").finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> VacantEntry<'_, 'a, T> {
+    /// Publishes `r` to the cell, returning the published reference.
+    ///
+    /// If the cell got initialized since the entry was created, `r` is
+    /// discarded and the current reference is returned instead.
+    #[inline]
+    pub fn publish(self, r: &'a T) -> &'a T {
+        self.cell.publish_if_empty(r)
+    }
+}
@@ -0,0 +1,58 @@
+use core::fmt::{Display, Formatter};
+
+#[cfg(feature = "alloc")]
+use crate::lazy_graph::CycleError;
+
+/// An error returned by the fallible operations of the crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The cell is already initialized.
+    AlreadyInitialized,
+    /// The cell is not initialized.
+    Uninitialized,
+    /// The operation timed out.
+    Timeout,
+    /// The function of a recursive [`LazyRefSeq`](crate::LazyRefSeq) made
+    /// the index depend on itself.
+    #[cfg(feature = "alloc")]
+    CyclicIndex(usize),
+    /// The dependencies of a node of a
+    /// [`LazyGraph`](crate::lazy_graph::LazyGraph) form a cycle.
+    #[cfg(feature = "alloc")]
+    Cycle(CycleError),
+}
+
+impl Display for Error {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::AlreadyInitialized => f.write_str("the cell is already initialized"),
+            Self::Uninitialized => f.write_str("the cell is not initialized"),
+            Self::Timeout => f.write_str("the operation timed out"),
+            #[cfg(feature = "alloc")]
+            Self::CyclicIndex(idx) => write!(f, "index {idx} depends on itself"),
+            #[cfg(feature = "alloc")]
+            Self::Cycle(_) => f.write_str("the dependencies form a cycle"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    #[inline]
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "alloc")]
+            Self::Cycle(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<CycleError> for Error {
+    #[inline]
+    fn from(err: CycleError) -> Self {
+        Self::Cycle(err)
+    }
+}
@@ -0,0 +1,153 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Backoff, LazyRef, SpinBackoff};
+
+/// A lock-free collector of the first `N` references published by racing
+/// producers.
+///
+/// Every [`push`](Self::push) claims the next slot, until all `N` are
+/// claimed, after which references are rejected. Once every claimed slot is
+/// filled, the references are available as a slice in the order they were
+/// claimed.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::FirstN;
+///
+/// let responses: Vec<u32> = (0..8).collect();
+/// let fastest = FirstN::<'_, u32, 3>::new();
+///
+/// std::thread::scope(|s| {
+///     for response in &responses {
+///         let fastest = &fastest;
+///         s.spawn(move || {
+///             let _ = fastest.push(response);
+///         });
+///     }
+///     let winners = fastest.wait_full();
+///     assert_eq!(winners.len(), 3);
+///     assert!(winners.iter().all(|r| responses.contains(r)));
+/// });
+/// ```
+pub struct FirstN<'a, T, const N: usize> {
+    slots: [LazyRef<'a, T>; N],
+    /// The number of the claimed slots.
+    claimed: AtomicUsize,
+    /// The number of the filled slots.
+    filled: AtomicUsize,
+}
+
+impl<T: Debug, const N: usize> Debug for FirstN<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries(self.slots.iter().filter_map(LazyRef::get))
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for FirstN<'_, T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> FirstN<'a, T, N> {
+    /// Creates a new empty collector.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slots: LazyRef::new_uninit_array(),
+            claimed: AtomicUsize::new(0),
+            filled: AtomicUsize::new(0),
+        }
+    }
+
+    /// Publishes `r` to the next free slot, returning its position.
+    ///
+    /// # Errors
+    ///
+    /// Returns `r` back if all the slots are claimed.
+    #[inline]
+    pub fn push(&self, r: &'a T) -> Result<usize, &'a T> {
+        let position = self
+            .claimed
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |claimed| {
+                (claimed < N).then_some(claimed + 1)
+            })
+            .map_err(|_| r)?;
+        self.slots[position].set(r);
+        self.filled.fetch_add(1, Ordering::Release);
+        Ok(position)
+    }
+
+    /// Returns the number of the filled slots.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.filled.load(Ordering::Relaxed)
+    }
+
+    /// Checks whether no slots are filled.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether all the slots are filled.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.filled.load(Ordering::Acquire) == N
+    }
+
+    /// Gets the collected references.
+    ///
+    /// Returns `None` if not all the slots are filled yet.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> Option<&[&'a T]> {
+        if !self.is_full() {
+            return None;
+        }
+        let slots: *const [LazyRef<'a, T>] = &self.slots;
+        // SAFETY:
+        // This is safe because `LazyRef` has the same layout as `&T` and all the slots
+        // are initialized, as the acquire load of `filled` has synchronized with every
+        // publication. Since all the slots are claimed, they are never written again
+        // through shared access.
+        Some(unsafe { &*(slots as *const [&'a T]) })
+    }
+
+    /// Waits until all the slots are filled, and returns the collected
+    /// references.
+    ///
+    /// The current thread spins, and yields with the `std` feature, while
+    /// waiting. If fewer than `N` references are ever pushed, this never
+    /// returns.
+    #[inline]
+    #[must_use]
+    pub fn wait_full(&self) -> &[&'a T] {
+        self.wait_full_with(SpinBackoff::default())
+    }
+
+    /// Like [`wait_full`](Self::wait_full), but waits between the checks with
+    /// `backoff`.
+    #[inline]
+    #[must_use]
+    pub fn wait_full_with(&self, mut backoff: impl Backoff) -> &[&'a T] {
+        loop {
+            if let Some(slice) = self.as_slice() {
+                return slice;
+            }
+            backoff.snooze();
+        }
+    }
+}
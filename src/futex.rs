@@ -0,0 +1,306 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{Error, LazyRef};
+
+/// The state of an empty cell without waiters.
+const EMPTY: u32 = 0;
+/// The state of an initialized cell.
+const PUBLISHED: u32 = 1;
+/// The state of an empty cell with waiters.
+const WAITING: u32 = 2;
+
+/// A [`LazyRef`] with a blocking [`wait`](Self::wait) for the lowest-latency
+/// hand-offs.
+///
+/// Waiting threads sleep on an adjacent 32-bit word: with a `futex` on
+/// Linux, with `WaitOnAddress` on Windows, and in a global table of condition
+/// variables elsewhere. Publications only
+/// make a system call if there are threads waiting.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::FutexLazyRef;
+///
+/// let batch = vec![1, 2, 3];
+/// let cell = FutexLazyRef::new();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| assert_eq!(cell.wait(), &[1, 2, 3]));
+///     cell.set(&batch);
+/// });
+/// ```
+pub struct FutexLazyRef<'a, T> {
+    inner: LazyRef<'a, T>,
+    state: AtomicU32,
+}
+
+impl<T: Debug> Debug for FutexLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_tuple("FutexLazyRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for FutexLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> FutexLazyRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+            state: AtomicU32::new(EMPTY),
+        }
+    }
+
+    /// Creates a new initialized cell.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized(r: &'a T) -> Self {
+        Self {
+            inner: LazyRef::new_initialized(r),
+            state: AtomicU32::new(PUBLISHED),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`], waking the waiting threads.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        if let Some(r) = self.get() {
+            return r;
+        }
+        let r = self.inner.get_or_init(f);
+        self.wake();
+        r
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`, waking the waiting threads.
+    #[inline]
+    pub fn set(&self, r: &'a T) {
+        self.inner.set(r);
+        self.wake();
+    }
+
+    /// Blocks the current thread until the cell is initialized, returning the
+    /// underlying reference.
+    #[inline]
+    pub fn wait(&self) -> &'a T {
+        loop {
+            if let Some(r) = self.get() {
+                return r;
+            }
+            if self.announce_waiting() {
+                sys::wait(&self.state, WAITING, None);
+            }
+        }
+    }
+
+    /// Blocks the current thread until the cell is initialized or `timeout`
+    /// elapses, returning the underlying reference.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if `timeout` elapses first.
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<&'a T, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(r) = self.get() {
+                return Ok(r);
+            }
+            let left = deadline
+                .checked_duration_since(Instant::now())
+                .ok_or(Error::Timeout)?;
+            if self.announce_waiting() {
+                sys::wait(&self.state, WAITING, Some(left));
+            }
+        }
+    }
+
+    /// Marks the cell as having waiters, returning `false` if it has been
+    /// initialized in the meantime.
+    #[inline]
+    fn announce_waiting(&self) -> bool {
+        match self
+            .state
+            .compare_exchange(EMPTY, WAITING, Ordering::Relaxed, Ordering::Acquire)
+        {
+            Ok(_) => true,
+            Err(state) => state == WAITING,
+        }
+    }
+
+    /// Marks the cell as initialized and wakes the waiting threads.
+    #[inline]
+    fn wake(&self) {
+        if self.state.load(Ordering::Relaxed) != PUBLISHED
+            && self.state.swap(PUBLISHED, Ordering::Release) == WAITING
+        {
+            sys::wake_all(&self.state);
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod sys {
+    use std::{ptr, sync::atomic::AtomicU32, time::Duration};
+
+    /// Blocks the current thread while `word` holds `expected`, for at most
+    /// `timeout`. May return spuriously.
+    pub(super) fn wait(word: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+        let timeout = timeout.map(|timeout| libc::timespec {
+            tv_sec: libc::time_t::try_from(timeout.as_secs()).unwrap_or(libc::time_t::MAX),
+            tv_nsec: libc::c_long::from(timeout.subsec_nanos().cast_signed()),
+        });
+        let timeout = timeout.as_ref().map_or(ptr::null(), ptr::from_ref);
+        // SAFETY:
+        // This is safe because `word` and `timeout` are valid for the duration of the
+        // call, and `FUTEX_WAIT` only reads them.
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                word.as_ptr(),
+                libc::FUTEX_WAIT | libc::FUTEX_PRIVATE_FLAG,
+                expected,
+                timeout,
+            );
+        }
+    }
+
+    /// Wakes all the threads waiting on `word`.
+    pub(super) fn wake_all(word: &AtomicU32) {
+        // SAFETY:
+        // This is safe because `FUTEX_WAKE` doesn't access the memory of `word`.
+        unsafe {
+            libc::syscall(
+                libc::SYS_futex,
+                word.as_ptr(),
+                libc::FUTEX_WAKE | libc::FUTEX_PRIVATE_FLAG,
+                i32::MAX,
+            );
+        }
+    }
+}
+
+#[cfg(windows)]
+mod sys {
+    use std::{ptr, sync::atomic::AtomicU32, time::Duration};
+
+    use windows_sys::Win32::System::Threading::{WaitOnAddress, WakeByAddressAll, INFINITE};
+
+    /// Blocks the current thread while `word` holds `expected`, for at most
+    /// `timeout`. May return spuriously.
+    pub(super) fn wait(word: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+        let timeout = timeout.map_or(INFINITE, |timeout| {
+            u32::try_from(timeout.as_nanos().div_ceil(1_000_000))
+                .map_or(INFINITE - 1, |ms| ms.min(INFINITE - 1))
+        });
+        // SAFETY:
+        // This is safe because `word` and `expected` are valid 32-bit values for the
+        // duration of the call, and `WaitOnAddress` only reads them.
+        unsafe {
+            WaitOnAddress(
+                word.as_ptr().cast(),
+                ptr::from_ref(&expected).cast(),
+                size_of::<u32>(),
+                timeout,
+            );
+        }
+    }
+
+    /// Wakes all the threads waiting on `word`.
+    pub(super) fn wake_all(word: &AtomicU32) {
+        // SAFETY:
+        // This is safe because `WakeByAddressAll` doesn't access the memory of `word`.
+        unsafe { WakeByAddressAll(word.as_ptr().cast()) };
+    }
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+mod sys {
+    use std::{
+        sync::{
+            atomic::{AtomicU32, Ordering},
+            Condvar, Mutex, MutexGuard, PoisonError,
+        },
+        time::Duration,
+    };
+
+    /// The number of the buckets of waiting threads.
+    const BUCKETS: usize = 64;
+
+    struct Bucket {
+        lock: Mutex<()>,
+        woken: Condvar,
+    }
+
+    static TABLE: [Bucket; BUCKETS] = [const {
+        Bucket {
+            lock: Mutex::new(()),
+            woken: Condvar::new(),
+        }
+    }; BUCKETS];
+
+    /// Locks the bucket of the threads waiting on `word`.
+    fn lock(word: &AtomicU32) -> (&'static Bucket, MutexGuard<'static, ()>) {
+        let bucket = &TABLE[(word.as_ptr().addr() >> 2) % BUCKETS];
+        let guard = bucket.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        (bucket, guard)
+    }
+
+    /// Blocks the current thread while `word` holds `expected`, for at most
+    /// `timeout`. May return spuriously.
+    pub(super) fn wait(word: &AtomicU32, expected: u32, timeout: Option<Duration>) {
+        let (bucket, guard) = lock(word);
+        if word.load(Ordering::Relaxed) != expected {
+            return;
+        }
+        match timeout {
+            Some(timeout) => drop(bucket.woken.wait_timeout(guard, timeout)),
+            None => drop(bucket.woken.wait(guard)),
+        }
+    }
+
+    /// Wakes all the threads waiting on `word`.
+    pub(super) fn wake_all(word: &AtomicU32) {
+        let (bucket, guard) = lock(word);
+        drop(guard);
+        bucket.woken.notify_all();
+    }
+}
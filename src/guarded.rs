@@ -0,0 +1,222 @@
+use std::{
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    sync::{Arc, Condvar, Mutex, PoisonError},
+};
+
+use crate::LazyRef;
+
+/// Runs `f` with a [`GuardedScope`], through which cells borrowed for `'env`
+/// can be handed out as `'static` [`GuardedLazyRef`]s, e.g. to plugin threads.
+///
+/// Before returning, even by unwinding, `guarded_scope` blocks until every
+/// [`GuardedLazyRef`] created in the scope has been dropped, so the cells
+/// are guaranteed to outlive them.
+///
+/// A guard that can't be dropped before the scope ends, e.g. because it is
+/// returned from `f` or forgotten, blocks the scope forever. Checking
+/// [`GuardedScope::live`] at the end of `f` finds such guards where none are
+/// expected to be alive.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{guarded_scope, LazyRef};
+///
+/// let value = 42;
+/// let cell = LazyRef::new_initialized(&value);
+///
+/// guarded_scope(|scope| {
+///     let guarded = scope.guard(&cell);
+///     std::thread::spawn(move || assert_eq!(guarded.get(), Some(&42)));
+/// });
+/// ```
+pub fn guarded_scope<'env, R>(f: impl FnOnce(&GuardedScope<'env>) -> R) -> R {
+    let scope = GuardedScope {
+        live: Arc::default(),
+        _env: PhantomData,
+    };
+    f(&scope)
+}
+
+/// A scope created by [`guarded_scope`], counting the [`GuardedLazyRef`]s
+/// created in it.
+///
+/// Only the cells living longer than the whole scope can be guarded:
+///
+/// ```compile_fail
+/// use lazy_ref::{guarded_scope, LazyRef};
+///
+/// guarded_scope(|scope| {
+///     let cell = LazyRef::<i32>::new();
+///     let _ = scope.guard(&cell);
+/// });
+/// ```
+///
+/// The guards themselves are `'static`, so nothing stops them from escaping
+/// the scope. An escaped guard blocks the end of the scope forever:
+///
+/// ```no_run
+/// use lazy_ref::{guarded_scope, LazyRef};
+///
+/// let cell = LazyRef::<i32>::new();
+/// // The scope waits for the returned guard, which is only dropped after it.
+/// let escaped = guarded_scope(|scope| scope.guard(&cell));
+/// ```
+pub struct GuardedScope<'env> {
+    live: Arc<Live>,
+    _env: PhantomData<&'env mut &'env ()>,
+}
+
+impl Debug for GuardedScope<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GuardedScope")
+            .field("live", &*self.live.lock())
+            .finish()
+    }
+}
+
+impl Drop for GuardedScope<'_> {
+    fn drop(&mut self) {
+        let mut live = self.live.lock();
+        while *live != 0 {
+            live = self
+                .live
+                .released
+                .wait(live)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+}
+
+impl<'env> GuardedScope<'env> {
+    /// Erases the lifetime of `cell`, returning a `'static` handle to it,
+    /// which keeps the scope from ending while it is alive.
+    #[inline]
+    #[must_use]
+    pub fn guard<T: Sync>(&self, cell: &'env LazyRef<'env, T>) -> GuardedLazyRef<T> {
+        GuardedLazyRef::new(std::ptr::from_ref(cell).cast(), Arc::clone(&self.live))
+    }
+
+    /// Returns the number of [`GuardedLazyRef`]s of this scope that are still
+    /// alive.
+    #[inline]
+    #[must_use]
+    pub fn live(&self) -> usize {
+        *self.live.lock()
+    }
+}
+
+/// A `'static` handle to a [`LazyRef`] borrowed by a [`GuardedScope`].
+///
+/// The references taken out of the cell are tied to the handle, which keeps
+/// the scope from ending until it is dropped.
+pub struct GuardedLazyRef<T> {
+    /// The cell of type `LazyRef<'env, T>` with its lifetime erased.
+    cell: *const (),
+    live: Arc<Live>,
+    _phantom: PhantomData<*mut T>,
+}
+
+// SAFETY:
+// The handle only gives shared access to the cell, which is `Sync`, and to the
+// references of type `&T`, which can be sent to another thread since `T: Sync`.
+unsafe impl<T: Sync> Send for GuardedLazyRef<T> {}
+
+// SAFETY:
+// The same as for `Send`.
+unsafe impl<T: Sync> Sync for GuardedLazyRef<T> {}
+
+impl<T> Clone for GuardedLazyRef<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self::new(self.cell, Arc::clone(&self.live))
+    }
+}
+
+impl<T: Debug> Debug for GuardedLazyRef<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GuardedLazyRef").field(self.cell()).finish()
+    }
+}
+
+impl<T> Drop for GuardedLazyRef<T> {
+    fn drop(&mut self) {
+        let mut live = self.live.lock();
+        *live -= 1;
+        if *live == 0 {
+            self.live.released.notify_all();
+        }
+    }
+}
+
+impl<T> GuardedLazyRef<T> {
+    /// Creates a new handle, registering it in `live`.
+    #[inline]
+    fn new(cell: *const (), live: Arc<Live>) -> Self {
+        *live.lock() += 1;
+        Self {
+            cell,
+            live,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&T> {
+        self.cell().get()
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> &'static T) -> &T
+    where
+        T: 'static,
+    {
+        self.cell().get_or_init(|| f())
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.cell().is_initialized()
+    }
+
+    /// Gets the cell with its lifetime shortened to the borrow of the handle.
+    #[inline]
+    fn cell(&self) -> &LazyRef<'_, T> {
+        // SAFETY:
+        // This is safe because the cell is borrowed for the lifetime of the scope,
+        // which cannot end while the handle is alive. Shortening the lifetime
+        // of the cell to the borrow of the handle only allows setting it with
+        // references that live at least as long as the handle, and
+        // `get_or_init` only accepts `'static` ones.
+        unsafe { &*self.cell.cast() }
+    }
+}
+
+/// The number of live [`GuardedLazyRef`]s of a scope.
+#[derive(Default)]
+struct Live {
+    count: Mutex<usize>,
+    released: Condvar,
+}
+
+impl Live {
+    /// Locks the counter, ignoring poisoning.
+    #[inline]
+    fn lock(&self) -> std::sync::MutexGuard<'_, usize> {
+        self.count.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
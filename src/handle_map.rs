@@ -0,0 +1,276 @@
+use alloc::vec::Vec;
+use core::fmt::{Debug, Formatter};
+
+use crate::{telemetry::SlowPath, LazyRef};
+
+/// A handle to a slot of a [`HandleMap`].
+///
+/// Handles to removed slots are stale: they are rejected by the map, even if
+/// the slot has been reused since.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Handle {
+    index: u32,
+    generation: u32,
+}
+
+impl Handle {
+    /// Returns the index of the slot.
+    #[inline]
+    #[must_use]
+    pub fn index(self) -> u32 {
+        self.index
+    }
+
+    /// Returns the generation of the slot the handle was created for.
+    #[inline]
+    #[must_use]
+    pub fn generation(self) -> u32 {
+        self.generation
+    }
+
+    /// Packs the handle into an opaque `u64`, for passing it through foreign
+    /// function interfaces such as `UniFFI` or JNI.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::RwLock;
+    ///
+    /// use lazy_ref::{Handle, HandleMap};
+    ///
+    /// static RESOURCES: RwLock<HandleMap<'static, String>> = RwLock::new(HandleMap::new());
+    ///
+    /// fn open() -> u64 {
+    ///     RESOURCES.write().unwrap().insert().to_bits()
+    /// }
+    ///
+    /// fn load(handle: u64) -> Option<&'static String> {
+    ///     let resources = RESOURCES.read().unwrap();
+    ///     resources.get_or_init(Handle::from_bits(handle), || {
+    ///         Box::leak(Box::new("decoded".to_string()))
+    ///     })
+    /// }
+    ///
+    /// let handle = open();
+    /// assert_eq!(load(handle).unwrap(), "decoded");
+    /// assert_eq!(load(handle ^ 1 << 32), None);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn to_bits(self) -> u64 {
+        (self.generation as u64) << 32 | self.index as u64
+    }
+
+    /// Unpacks a handle packed by [`to_bits`](Self::to_bits).
+    ///
+    /// Any `u64` is accepted: the handles unpacked from the values that were
+    /// not packed by a map are rejected by it like the stale ones.
+    #[inline]
+    #[must_use]
+    // The truncation is intended: the halves are the index and the generation.
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn from_bits(bits: u64) -> Self {
+        Self {
+            index: bits as u32,
+            generation: (bits >> 32) as u32,
+        }
+    }
+}
+
+/// A slot of a [`HandleMap`].
+struct Slot<'a, T> {
+    cell: LazyRef<'a, T>,
+    generation: u32,
+    occupied: bool,
+}
+
+/// A table of lazily bound immutable references addressed by generational
+/// [`Handle`]s.
+///
+/// Slots are allocated and removed through unique access, while the cells of
+/// the slots can be bound through shared access like any [`LazyRef`].
+/// Removing a slot, including by [`clear`](Self::clear), invalidates all the
+/// handles to it.
+///
+/// Generations wrap around after `u32::MAX` reuses of the same slot, after
+/// which a stale handle may be accepted again.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::HandleMap;
+///
+/// let textures = ["grass".to_string(), "stone".to_string()];
+/// let mut table = HandleMap::new();
+///
+/// let grass = table.insert();
+/// assert_eq!(table.get(grass), None);
+/// assert_eq!(table.get_or_init(grass, || &textures[0]), Some(&textures[0]));
+///
+/// table.clear();
+/// assert!(!table.contains(grass));
+/// assert_eq!(table.get_or_init(grass, || &textures[1]), None);
+///
+/// let stone = table.insert();
+/// assert_eq!(stone.index(), grass.index());
+/// assert_eq!(table.get_or_init(stone, || &textures[1]), Some(&textures[1]));
+/// ```
+pub struct HandleMap<'a, T> {
+    slots: Vec<Slot<'a, T>>,
+    /// The indices of the free slots.
+    free: Vec<u32>,
+}
+
+impl<T: Debug> Debug for HandleMap<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl<T> Default for HandleMap<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> HandleMap<'a, T> {
+    /// Creates a new empty map.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Returns the number of occupied slots.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.len() - self.free.len()
+    }
+
+    /// Checks whether there are no occupied slots.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Occupies a slot with an empty cell, returning the handle to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of slots overflows `u32`.
+    #[inline]
+    pub fn insert(&mut self) -> Handle {
+        if let Some(index) = self.free.pop() {
+            let slot = &mut self.slots[index as usize];
+            slot.occupied = true;
+            return Handle {
+                index,
+                generation: slot.generation,
+            };
+        }
+        let index = u32::try_from(self.slots.len()).expect("too many slots");
+        self.slots.push(Slot {
+            cell: LazyRef::new(),
+            generation: 0,
+            occupied: true,
+        });
+        Handle {
+            index,
+            generation: 0,
+        }
+    }
+
+    /// Checks whether `handle` refers to an occupied slot.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, handle: Handle) -> bool {
+        self.cell(handle).is_some()
+    }
+
+    /// Returns the cell of the slot `handle` refers to.
+    ///
+    /// Returns `None` if the handle is stale.
+    #[inline]
+    #[must_use]
+    pub fn cell(&self, handle: Handle) -> Option<&LazyRef<'a, T>> {
+        let slot = self.slots.get(handle.index as usize)?;
+        (slot.occupied && slot.generation == handle.generation).then_some(&slot.cell)
+    }
+
+    /// Gets the reference bound to the slot `handle` refers to.
+    ///
+    /// Returns `None` if the handle is stale or the slot is not bound yet.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, handle: Handle) -> Option<&'a T> {
+        self.cell(handle)?.get()
+    }
+
+    /// Gets the reference bound to the slot `handle` refers to, binding it
+    /// with `f` if the slot was not bound yet.
+    ///
+    /// Returns `None` if the handle is stale.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, handle: Handle, f: impl FnOnce() -> &'a T) -> Option<&'a T> {
+        let cell = self.cell(handle)?;
+        let mut slow = SlowPath::new("HandleMap");
+        let r = cell.get_or_init(|| slow.compute(f));
+        Some(slow.finish(r))
+    }
+
+    /// Frees the slot `handle` refers to, invalidating all the handles to
+    /// it, and returns the reference bound to it.
+    ///
+    /// Returns `None` if the handle is stale or the slot was not bound.
+    #[inline]
+    pub fn remove(&mut self, handle: Handle) -> Option<&'a T> {
+        self.cell(handle)?;
+        let slot = &mut self.slots[handle.index as usize];
+        let r = Self::free_slot(slot);
+        self.free.push(handle.index);
+        r
+    }
+
+    /// Frees all the slots, invalidating all the handles.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.free.clear();
+        for (index, slot) in (0..).zip(&mut self.slots) {
+            if slot.occupied {
+                let _ = Self::free_slot(slot);
+            }
+            self.free.push(index);
+        }
+    }
+
+    /// Iterates over the handles to the occupied slots and their cells.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (Handle, &LazyRef<'a, T>)> {
+        (0..)
+            .zip(&self.slots)
+            .filter(|(_, slot)| slot.occupied)
+            .map(|(index, slot)| {
+                let handle = Handle {
+                    index,
+                    generation: slot.generation,
+                };
+                (handle, &slot.cell)
+            })
+    }
+
+    /// Frees `slot`, returning the reference bound to it.
+    #[inline]
+    fn free_slot(slot: &mut Slot<'a, T>) -> Option<&'a T> {
+        slot.occupied = false;
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.cell.take_owned()
+    }
+}
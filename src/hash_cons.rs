@@ -0,0 +1,245 @@
+use std::{
+    boxed::Box,
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, Hash, RandomState},
+    marker::PhantomData,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+use crate::{map::RawMap, LazyRef};
+
+/// An identifier of a value interned by a [`HashConsing`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConsId(usize);
+
+impl ConsId {
+    /// Returns the index of the identifier.
+    #[inline]
+    #[must_use]
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The number of the buckets of an [`IdTable`], enough for any index.
+const BUCKETS: usize = usize::BITS as usize;
+
+/// A lock-free append-only table of cells indexed by the identifiers, made
+/// of the buckets of `2^k` cells allocated on demand.
+struct IdTable<'a, T> {
+    /// The pointers to the first cells of the buckets.
+    buckets: [AtomicPtr<LazyRef<'a, T>>; BUCKETS],
+    _phantom: PhantomData<Box<[LazyRef<'a, T>]>>,
+}
+
+impl<T> Drop for IdTable<'_, T> {
+    fn drop(&mut self) {
+        for (bucket, ptr) in self.buckets.iter_mut().enumerate() {
+            let ptr = *ptr.get_mut();
+            if !ptr.is_null() {
+                // SAFETY:
+                // This is safe because every non-null bucket is created from a boxed
+                // slice of `1 << bucket` cells by `cell_or_alloc`.
+                drop(unsafe {
+                    Box::from_raw(core::ptr::slice_from_raw_parts_mut(ptr, 1 << bucket))
+                });
+            }
+        }
+    }
+}
+
+impl<'a, T> IdTable<'a, T> {
+    /// Creates a new table without buckets.
+    #[inline]
+    const fn new() -> Self {
+        Self {
+            buckets: [const { AtomicPtr::new(core::ptr::null_mut()) }; BUCKETS],
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the bucket of `idx` and the offset of `idx` in it.
+    #[inline]
+    fn locate(idx: usize) -> Option<(usize, usize)> {
+        let pos = idx.checked_add(1)?;
+        let bucket = pos.ilog2() as usize;
+        Some((bucket, pos - (1 << bucket)))
+    }
+
+    /// Returns the cell at `idx`.
+    ///
+    /// Returns `None` if the bucket of the cell is not allocated.
+    #[inline]
+    fn cell(&self, idx: usize) -> Option<&LazyRef<'a, T>> {
+        let (bucket, offset) = Self::locate(idx)?;
+        let ptr = self.buckets[bucket].load(Ordering::Acquire);
+        // SAFETY:
+        // This is safe because every non-null bucket points to `1 << bucket` cells,
+        // which are not deallocated until the table is dropped.
+        unsafe { ptr.as_ref().map(|_| &*ptr.add(offset)) }
+    }
+
+    /// Returns the cell at `idx`, allocating its bucket if needed.
+    #[inline]
+    fn cell_or_alloc(&self, idx: usize) -> &LazyRef<'a, T> {
+        if let Some(cell) = self.cell(idx) {
+            return cell;
+        }
+        let (bucket, offset) = Self::locate(idx).expect("too many identifiers");
+        let cells: Box<[LazyRef<'a, T>]> = (0..1_usize << bucket).map(|_| LazyRef::new()).collect();
+        let new = Box::into_raw(cells).cast::<LazyRef<'a, T>>();
+        let ptr = match self.buckets[bucket].compare_exchange(
+            core::ptr::null_mut(),
+            new,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => new,
+            Err(current) => {
+                // SAFETY:
+                // This is safe because `new` has just been created from a boxed slice of
+                // `1 << bucket` cells and has not been published.
+                drop(unsafe {
+                    Box::from_raw(core::ptr::slice_from_raw_parts_mut(new, 1 << bucket))
+                });
+                current
+            }
+        };
+        // SAFETY:
+        // This is safe because the bucket points to `1 << bucket` cells, which are
+        // not deallocated until the table is dropped.
+        unsafe { &*ptr.add(offset) }
+    }
+}
+
+/// A non-blocking hash-consing store, assigning stable identifiers to the
+/// distinct values of immutable references.
+///
+/// Equal values get the same identifier, which resolves to the first
+/// interned reference among them. The identifiers are unique, but may skip
+/// numbers when equal values are interned concurrently.
+///
+/// The values are hashed with `S`, which defaults to the hasher of
+/// [`HashMap`](std::collections::HashMap).
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::HashConsing;
+///
+/// let terms: Vec<String> = vec!["x + 1".into(), "y".into(), "x + 1".into()];
+/// let store = HashConsing::new();
+///
+/// let first = store.insert(&terms[0]);
+/// let second = store.insert(&terms[1]);
+/// assert_eq!(store.insert(&terms[2]), first);
+/// assert_ne!(first, second);
+///
+/// assert!(std::ptr::eq(store.resolve(first).unwrap(), &terms[0]));
+/// assert_eq!(store.id(&"y".to_string()), Some(second));
+/// assert_eq!(store.len(), 2);
+/// ```
+pub struct HashConsing<'a, T, S = RandomState> {
+    ids: RawMap<&'a T, ConsId, S>,
+    values: IdTable<'a, T>,
+    next: AtomicUsize,
+}
+
+impl<T: Debug, S> Debug for HashConsing<'_, T, S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_map();
+        self.ids.for_each(|value, id| {
+            d.entry(id, value);
+        });
+        d.finish()
+    }
+}
+
+impl<T, S: Default> Default for HashConsing<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T> HashConsing<'_, T> {
+    /// Creates a new empty store.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<'a, T, S> HashConsing<'a, T, S> {
+    /// Creates a new empty store hashing the values with `hasher`.
+    #[inline]
+    #[must_use]
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self {
+            ids: RawMap::with_hasher(hasher),
+            values: IdTable::new(),
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of distinct interned values.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    /// Checks whether no values have been interned.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Gets the reference identified by `id`.
+    ///
+    /// Returns `None` if `id` wasn't returned by this store.
+    #[inline]
+    #[must_use]
+    pub fn resolve(&self, id: ConsId) -> Option<&'a T> {
+        self.values.cell(id.0)?.get()
+    }
+}
+
+impl<'a, T: Hash + Eq, S: BuildHasher> HashConsing<'a, T, S> {
+    /// Interns `value`, returning the identifier of the values equal to it.
+    ///
+    /// Many threads may call `insert` concurrently with equal values. In this
+    /// case all of them get the same identifier, which resolves as soon as
+    /// any of them returns.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the identifiers overflow `usize`.
+    #[inline]
+    pub fn insert(&self, value: &'a T) -> ConsId {
+        let (interned, &id) = self
+            .ids
+            .get_or_insert_with(value, |_| ConsId(self.next.fetch_add(1, Ordering::Relaxed)));
+        // Every caller publishes the reference, so that the identifier resolves
+        // even if the caller that assigned it hasn't published it yet.
+        let _ = self.values.cell_or_alloc(id.0).get_or_init(|| interned);
+        id
+    }
+
+    /// Gets the identifier of the values equal to `value`.
+    ///
+    /// Returns `None` if no such value has been interned. Like
+    /// [`insert`](Self::insert), publishes the interned reference, so that
+    /// the identifier resolves even if the thread interning it hasn't
+    /// returned yet.
+    #[inline]
+    #[must_use]
+    pub fn id(&self, value: &T) -> Option<ConsId> {
+        let (interned, &id) = self.ids.get(value)?;
+        let _ = self.values.cell_or_alloc(id.0).get_or_init(|| interned);
+        Some(id)
+    }
+}
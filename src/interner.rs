@@ -0,0 +1,115 @@
+use std::{
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use crate::map::RawMap;
+
+/// A non-blocking interner of immutable references.
+///
+/// Deduplicates equal values by returning the same reference for all of them.
+///
+/// The values are hashed with `S`, which defaults to the hasher of
+/// [`HashMap`](std::collections::HashMap).
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::RefInterner;
+///
+/// let words: Vec<String> = vec!["lazy".into(), "ref".into(), "lazy".into()];
+/// let interner = RefInterner::new();
+///
+/// let first = interner.intern(words[0].as_str());
+/// let second = interner.intern(words[2].as_str());
+/// assert!(std::ptr::eq(first, second));
+/// assert!(std::ptr::eq(first, &words[0][..]));
+///
+/// let _ = interner.intern(words[1].as_str());
+/// assert_eq!(interner.len(), 2);
+/// assert_eq!(interner.get("ref"), Some("ref"));
+/// assert_eq!(interner.get("cell"), None);
+/// ```
+pub struct RefInterner<'a, T: ?Sized, S = RandomState> {
+    map: RawMap<&'a T, (), S>,
+}
+
+impl<T: Debug + ?Sized, S> Debug for RefInterner<'_, T, S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_set();
+        self.map.for_each(|value, ()| {
+            d.entry(value);
+        });
+        d.finish()
+    }
+}
+
+impl<T: ?Sized, S: Default> Default for RefInterner<'_, T, S> {
+    #[inline]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<T: ?Sized> RefInterner<'_, T> {
+    /// Creates a new empty interner.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_hasher(RandomState::new())
+    }
+}
+
+impl<T: ?Sized, S> RefInterner<'_, T, S> {
+    /// Creates a new empty interner hashing the values with `hasher`.
+    #[inline]
+    #[must_use]
+    pub const fn with_hasher(hasher: S) -> Self {
+        Self {
+            map: RawMap::with_hasher(hasher),
+        }
+    }
+
+    /// Returns the number of distinct interned values.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Checks whether no values have been interned.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, T: Hash + Eq + ?Sized, S: BuildHasher> RefInterner<'a, T, S> {
+    /// Interns `value`, returning the reference to the first interned value
+    /// equal to it.
+    ///
+    /// Many threads may call `intern` concurrently with equal values. In this
+    /// case all of them get the same reference.
+    #[inline]
+    pub fn intern(&self, value: &'a T) -> &'a T {
+        self.map.get_or_insert_with(value, |_| ()).0
+    }
+
+    /// Gets the interned reference equal to `value`.
+    ///
+    /// Returns `None` if no such value has been interned.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, value: &T) -> Option<&'a T> {
+        self.map.get(value).map(|(interned, ())| *interned)
+    }
+
+    /// Checks whether a value equal to `value` has been interned.
+    #[inline]
+    #[must_use]
+    pub fn contains(&self, value: &T) -> bool {
+        self.get(value).is_some()
+    }
+}
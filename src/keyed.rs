@@ -0,0 +1,161 @@
+use core::fmt::{Debug, Formatter};
+
+use crossbeam_utils::atomic::AtomicCell;
+
+/// A non-blocking single-slot memo cell holding an immutable reference
+/// together with the key it was computed for.
+///
+/// The key and the reference are always published and read as a single
+/// atomic unit.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::KeyedLazyRef;
+///
+/// let squares: Vec<usize> = (0..10).map(|x| x * x).collect();
+/// let memo = KeyedLazyRef::new();
+///
+/// assert_eq!(memo.get_or_init_for(3, || &squares[3]), &9);
+/// assert_eq!(memo.get_for(&3), Some(&9));
+/// assert_eq!(memo.get_for(&4), None);
+///
+/// assert_eq!(memo.get_or_init_for(4, || &squares[4]), &16);
+/// assert_eq!(memo.get_for(&3), None);
+/// ```
+///
+/// `KeyedLazyRef` is invariant over the underlying reference, so the
+/// following example wouldn't compile.
+///
+/// ```compile_fail
+/// use lazy_ref::KeyedLazyRef;
+///
+/// let memo = KeyedLazyRef::new();
+/// {
+///     let value = 1;
+///     let _ = memo.get_or_init_for(1, || &value);
+/// }
+/// let x = memo.get_for(&1).unwrap();
+/// assert_eq!(x, &1);
+/// ```
+pub struct KeyedLazyRef<'a, K, T> {
+    entry: AtomicCell<Option<(K, &'a T)>>,
+}
+
+impl<K: Copy, T> Clone for KeyedLazyRef<'_, K, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            entry: AtomicCell::new(self.entry.load()),
+        }
+    }
+}
+
+impl<K: Copy + PartialEq, T: PartialEq> PartialEq for KeyedLazyRef<'_, K, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<K: Copy + Eq, T: Eq> Eq for KeyedLazyRef<'_, K, T> {}
+
+impl<K: Copy + Debug, T: Debug> Debug for KeyedLazyRef<'_, K, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("KeyedLazyRef");
+        match self.get() {
+            Some((key, v)) => d.field(&key).field(v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<K, T> Default for KeyedLazyRef<'_, K, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, K, T> KeyedLazyRef<'a, K, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            entry: AtomicCell::new(None),
+        }
+    }
+
+    /// Creates a new cell initialized with `r` computed for `key`.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized(key: K, r: &'a T) -> Self {
+        Self {
+            entry: AtomicCell::new(Some((key, r))),
+        }
+    }
+
+    /// Consumes the cell, returning the wrapped key and reference.
+    /// Returns `None` if the cell was empty.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<(K, &'a T)> {
+        self.entry.into_inner()
+    }
+
+    /// Sets the contents of this cell to `r` computed for `key`.
+    #[inline]
+    pub fn set(&self, key: K, r: &'a T) {
+        self.entry.store(Some((key, r)));
+    }
+}
+
+impl<'a, K: Copy, T> KeyedLazyRef<'a, K, T> {
+    /// Gets the stored key and the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<(K, &'a T)> {
+        self.entry.load()
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.get().is_some()
+    }
+}
+
+impl<'a, K: Copy + Eq, T> KeyedLazyRef<'a, K, T> {
+    /// Gets the underlying reference if it was computed for `key`.
+    ///
+    /// Returns `None` if the cell is empty or holds a reference computed for
+    /// another key.
+    #[inline]
+    #[must_use]
+    pub fn get_for(&self, key: &K) -> Option<&'a T> {
+        self.get()
+            .and_then(|(stored, r)| (stored == *key).then_some(r))
+    }
+
+    /// Gets the underlying reference if it was computed for `key`, otherwise
+    /// replaces the contents of the cell with the reference returned by `f`.
+    ///
+    /// Many threads may call `get_or_init_for` concurrently. In this case
+    /// multiple functions can be executed, and the last published entry is
+    /// kept.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init_for(&self, key: K, f: impl FnOnce() -> &'a T) -> &'a T {
+        self.get_for(&key).unwrap_or_else(|| {
+            let r = f();
+            self.set(key, r);
+            r
+        })
+    }
+}
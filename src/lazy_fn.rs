@@ -0,0 +1,215 @@
+use std::{
+    borrow::{Borrow, ToOwned},
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, Hash, RandomState},
+    vec::Vec,
+};
+
+use crate::{map::RawMap, telemetry::SlowPath};
+
+/// A thread-safe memoized function returning immutable references.
+///
+/// Every argument is computed with the wrapped function on the first call
+/// and kept in a lock-free memo table afterwards. The function may be called
+/// multiple times for the same argument when it is called from many threads
+/// concurrently, but every caller gets the first memoized reference.
+///
+/// The arguments are hashed with `S`, which defaults to the hasher of
+/// [`HashMap`](std::collections::HashMap).
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyFn;
+///
+/// let arena: Vec<String> = vec!["zero".into(), "one".into(), "two".into()];
+/// let name = LazyFn::new(|n: &usize| &arena[*n]);
+///
+/// assert_eq!(name.call(1), "one");
+/// assert_eq!(name.get(&1), Some(&arena[1]));
+/// assert_eq!(name.get(&2), None);
+/// assert_eq!(name.len(), 1);
+/// ```
+pub struct LazyFn<'a, A, T, F, S = RandomState> {
+    memo: RawMap<A, &'a T, S>,
+    f: F,
+}
+
+impl<A: Debug, T: Debug, F, S> Debug for LazyFn<'_, A, T, F, S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LazyFn").field(&self.memo).finish()
+    }
+}
+
+impl<'a, A, T, F> LazyFn<'a, A, T, F>
+where
+    A: Hash + Eq,
+    F: Fn(&A) -> &'a T,
+{
+    /// Wraps `f` into a memoized function.
+    #[inline]
+    #[must_use]
+    pub fn new(f: F) -> Self {
+        Self::with_hasher(f, RandomState::new())
+    }
+}
+
+impl<'a, A, T, F, S> LazyFn<'a, A, T, F, S>
+where
+    A: Hash + Eq,
+    F: Fn(&A) -> &'a T,
+    S: BuildHasher,
+{
+    /// Wraps `f` into a memoized function hashing the arguments with
+    /// `hasher`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::{collections::hash_map::DefaultHasher, hash::BuildHasherDefault};
+    ///
+    /// use lazy_ref::LazyFn;
+    ///
+    /// let arena = ["zero", "one"];
+    /// let name = LazyFn::with_hasher(
+    ///     |n: &u8| &arena[usize::from(*n)],
+    ///     BuildHasherDefault::<DefaultHasher>::default(),
+    /// );
+    /// assert_eq!(name.call(1), &"one");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_hasher(f: F, hasher: S) -> Self {
+        Self {
+            memo: RawMap::with_hasher(hasher),
+            f,
+        }
+    }
+
+    /// Calls the function with `arg`, returning the memoized reference if
+    /// the function has already been called with an equal argument.
+    #[inline]
+    pub fn call(&self, arg: A) -> &'a T {
+        self.memoize(arg, |arg| (self.f)(arg))
+    }
+
+    /// Calls the function with an owned copy of `arg`, returning the memoized
+    /// reference if the function has already been called with an equal
+    /// argument.
+    ///
+    /// The copy is only made when the memoized reference is missing, so
+    /// looking up, for example, `String` arguments by `&str` doesn't allocate
+    /// on every call.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyFn;
+    ///
+    /// let lengths: Vec<usize> = (0..16).collect();
+    /// let len = LazyFn::new(|word: &String| &lengths[word.len()]);
+    ///
+    /// assert_eq!(len.call_borrowed("lazy"), &4);
+    /// assert_eq!(len.call_borrowed("lazy"), &4);
+    /// assert_eq!(len.get("lazy"), Some(&4));
+    /// assert_eq!(len.len(), 1);
+    /// ```
+    #[inline]
+    pub fn call_borrowed<Q>(&self, arg: &Q) -> &'a T
+    where
+        A: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = A> + ?Sized,
+    {
+        match self.get(arg) {
+            Some(r) => r,
+            None => self.call(arg.to_owned()),
+        }
+    }
+
+    /// Gets the memoized reference for `arg` without calling the function.
+    ///
+    /// Returns `None` if the function hasn't been called with an equal
+    /// argument.
+    #[inline]
+    #[must_use]
+    pub fn get<Q>(&self, arg: &Q) -> Option<&'a T>
+    where
+        A: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.memo.get(arg).map(|(_, r)| *r)
+    }
+
+    /// Returns the number of memoized arguments.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.memo.len()
+    }
+
+    /// Checks whether no arguments have been memoized.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Serializes a point-in-time snapshot of the memoized arguments as a map
+    /// from the arguments to their references.
+    ///
+    /// The entries are collected before serializing, so the serializer sees
+    /// a fixed set of them even if arguments are memoized concurrently. The
+    /// signature matches `#[serde(serialize_with = "...")]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `serializer`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyFn;
+    ///
+    /// let arena = ["zero", "one", "two"];
+    /// let name = LazyFn::new(|n: &usize| &arena[*n]);
+    /// let _ = name.call(1);
+    ///
+    /// let mut json = Vec::new();
+    /// name.snapshot_serialize(&mut serde_json::Serializer::new(&mut json))
+    ///     .unwrap();
+    /// assert_eq!(json, br#"{"1":"one"}"#);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn snapshot_serialize<Ser: serde::Serializer>(
+        &self,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        A: serde::Serialize,
+        T: serde::Serialize,
+    {
+        serializer.collect_map(self.entries())
+    }
+
+    /// Returns the memoized reference for `arg`, memoizing the one returned
+    /// by `f` if it is missing.
+    #[inline]
+    pub(crate) fn memoize(&self, arg: A, f: impl FnOnce(&A) -> &'a T) -> &'a T {
+        let mut slow = SlowPath::new("LazyFn");
+        let r = self
+            .memo
+            .get_or_insert_with(arg, |arg| slow.compute(|| f(arg)))
+            .1;
+        slow.finish(r)
+    }
+
+    /// Collects the memoized arguments and their references.
+    #[inline]
+    pub(crate) fn entries(&self) -> Vec<(&A, &'a T)> {
+        let mut entries = Vec::with_capacity(self.len());
+        self.memo.for_each(|arg, r| entries.push((arg, *r)));
+        entries
+    }
+}
@@ -0,0 +1,175 @@
+use core::cell::OnceCell;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+
+#[cfg(feature = "std")]
+use crate::{BroadcastRef, FutexLazyRef};
+use crate::{LazyRef, RankedLazyRef, StampedLazyRef};
+
+/// A common interface of lazy-initialized cells holding immutable references.
+///
+/// Allows generic code to stay agnostic of the exact lazy primitive the caller
+/// chooses.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::OnceLock;
+/// use lazy_ref::{LazyGet, LazyRef};
+///
+/// fn first_or<'a, C: LazyGet<'a, usize>>(cell: &C, values: &'a [usize]) -> &'a usize {
+///     cell.get_or_init(|| &values[0])
+/// }
+///
+/// let values = vec![1, 2, 3];
+///
+/// let lazy_ref = LazyRef::new();
+/// assert_eq!(first_or(&lazy_ref, &values), &1);
+///
+/// let once_lock = OnceLock::new();
+/// assert_eq!(first_or(&once_lock, &values), &1);
+/// assert!(LazyGet::is_initialized(&once_lock));
+/// ```
+pub trait LazyGet<'a, T: 'a> {
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[must_use]
+    fn get(&self) -> Option<&'a T>;
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    #[must_use]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T;
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    fn is_initialized(&self) -> bool {
+        self.get().is_some()
+    }
+}
+
+impl<'a, T> LazyGet<'a, T> for LazyRef<'a, T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        LazyRef::get(self)
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        LazyRef::get_or_init(self, f)
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        LazyRef::is_initialized(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> LazyGet<'a, T> for OnceLock<&'a T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        OnceLock::get(self).copied()
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        OnceLock::<&'a T>::get_or_init(self, f)
+    }
+}
+
+impl<'a, T> LazyGet<'a, T> for OnceCell<&'a T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        OnceCell::get(self).copied()
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        OnceCell::<&'a T>::get_or_init(self, f)
+    }
+}
+
+impl<'a, T> LazyGet<'a, T> for StampedLazyRef<'a, T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        StampedLazyRef::get(self)
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        StampedLazyRef::get_or_init(self, f)
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        StampedLazyRef::is_initialized(self)
+    }
+}
+
+/// Initializes an empty cell with the lowest rank, so that the reference can
+/// still be replaced by [`RankedLazyRef::get_or_init_preferring`].
+impl<'a, T> LazyGet<'a, T> for RankedLazyRef<'a, T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        RankedLazyRef::get(self)
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        self.get_or_init_preferring(0, f)
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        RankedLazyRef::is_initialized(self)
+    }
+}
+
+/// Initializes an empty cell by publishing the reference to its readers.
+#[cfg(feature = "std")]
+impl<'a, T> LazyGet<'a, T> for BroadcastRef<'a, T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        BroadcastRef::get(self)
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        if let Some(r) = self.get() {
+            return r;
+        }
+        let r = f();
+        match self.set(r) {
+            Ok(()) => r,
+            Err(_) => self
+                .get()
+                .expect("the cell has been published by another writer"),
+        }
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        BroadcastRef::is_initialized(self)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T> LazyGet<'a, T> for FutexLazyRef<'a, T> {
+    #[inline]
+    fn get(&self) -> Option<&'a T> {
+        FutexLazyRef::get(self)
+    }
+
+    #[inline]
+    fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        FutexLazyRef::get_or_init(self, f)
+    }
+
+    #[inline]
+    fn is_initialized(&self) -> bool {
+        FutexLazyRef::is_initialized(self)
+    }
+}
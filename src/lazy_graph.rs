@@ -0,0 +1,376 @@
+//! A graph of lazy-initialized immutable references depending on each other.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use lazy_ref::lazy_graph::LazyGraph;
+//!
+//! let arena: Vec<String> = vec!["config".into(), "database".into(), "server".into()];
+//!
+//! let mut graph = LazyGraph::new();
+//! let config = graph.add_node(|_| &arena[0]);
+//! let database = graph.add_node(|deps| {
+//!     assert_eq!(deps, [&arena[0]]);
+//!     &arena[1]
+//! });
+//! let server = graph.add_node(|deps| {
+//!     assert_eq!(deps, [&arena[0], &arena[1]]);
+//!     &arena[2]
+//! });
+//! graph.add_dependency(database, config);
+//! graph.add_dependency(server, config);
+//! graph.add_dependency(server, database);
+//!
+//! assert_eq!(graph.resolve(server), Ok(&arena[2]));
+//! assert_eq!(graph.get(database), Some(&arena[1]));
+//!
+//! graph.add_dependency(config, server);
+//! let cycle = graph.add_node(|_| &arena[0]);
+//! graph.add_dependency(cycle, cycle);
+//! assert!(matches!(graph.resolve(cycle), Err(lazy_ref::Error::Cycle(_))));
+//! ```
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::fmt::{Debug, Display, Formatter};
+#[cfg(feature = "std")]
+use core::num::NonZeroUsize;
+#[cfg(feature = "std")]
+use std::{
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    thread,
+};
+
+use crate::{Error, LazyRef};
+
+/// An identifier of a node of a [`LazyGraph`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeId(usize);
+
+/// The details of an [`Error::Cycle`], returned when the dependencies of a
+/// node form a cycle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct CycleError {
+    node: NodeId,
+}
+
+impl Display for CycleError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "node {} depends on itself", self.node.0)
+    }
+}
+
+impl core::error::Error for CycleError {}
+
+impl CycleError {
+    /// Returns the node found to depend on itself.
+    #[inline]
+    #[must_use]
+    pub fn node(&self) -> NodeId {
+        self.node
+    }
+}
+
+type Initializer<'a, T> = Box<dyn Fn(&[&'a T]) -> &'a T + Send + Sync + 'a>;
+
+struct Node<'a, T> {
+    cell: LazyRef<'a, T>,
+    dependencies: Vec<NodeId>,
+    init: Initializer<'a, T>,
+}
+
+/// A graph of lazy-initialized immutable references, where every node is
+/// initialized from the references of the nodes it depends on.
+///
+/// Nodes are initialized on demand through [`resolve`](Self::resolve), which
+/// initializes the dependencies first. Like [`LazyRef`], the graph can be
+/// resolved from many threads concurrently, in which case multiple
+/// initializers of the same node can be executed. With the `std` feature,
+/// [`resolve_concurrent`](Self::resolve_concurrent) initializes independent
+/// nodes in parallel.
+pub struct LazyGraph<'a, T> {
+    nodes: Vec<Node<'a, T>>,
+}
+
+impl<T: Debug> Debug for LazyGraph<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_map()
+            .entries(
+                self.nodes
+                    .iter()
+                    .enumerate()
+                    .map(|(id, node)| (id, (&node.cell, &node.dependencies))),
+            )
+            .finish()
+    }
+}
+
+impl<T> Default for LazyGraph<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> LazyGraph<'a, T> {
+    /// Creates a new empty graph.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { nodes: Vec::new() }
+    }
+
+    /// Adds a new node initialized with `init`, which is given the references
+    /// of the node's dependencies in the order they were added.
+    #[inline]
+    pub fn add_node(&mut self, init: impl Fn(&[&'a T]) -> &'a T + Send + Sync + 'a) -> NodeId {
+        self.nodes.push(Node {
+            cell: LazyRef::new(),
+            dependencies: Vec::new(),
+            init: Box::new(init),
+        });
+        NodeId(self.nodes.len() - 1)
+    }
+
+    /// Declares that `node` depends on `dependency`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the nodes doesn't belong to this graph.
+    #[inline]
+    pub fn add_dependency(&mut self, node: NodeId, dependency: NodeId) {
+        assert!(dependency.0 < self.nodes.len(), "unknown dependency node");
+        self.nodes[node.0].dependencies.push(dependency);
+    }
+
+    /// Gets the reference of `node`.
+    ///
+    /// Returns `None` if the node is not initialized yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node doesn't belong to this graph.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, node: NodeId) -> Option<&'a T> {
+        self.nodes[node.0].cell.get()
+    }
+
+    /// Gets the reference of `node`, initializing it and its dependencies if
+    /// they are not initialized yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cycle`] if an uninitialized node transitively depends
+    /// on itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node doesn't belong to this graph.
+    pub fn resolve(&self, node: NodeId) -> Result<&'a T, Error> {
+        if let Some(r) = self.get(node) {
+            return Ok(r);
+        }
+        for id in self.pending(node)? {
+            self.initialize(id);
+        }
+        Ok(self.get(node).expect("the node has just been initialized"))
+    }
+
+    /// Returns the uninitialized nodes `node` transitively depends on,
+    /// including itself, so that every node follows its dependencies.
+    fn pending(&self, node: NodeId) -> Result<Vec<NodeId>, CycleError> {
+        let mut order = Vec::new();
+        let mut on_path = vec![false; self.nodes.len()];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut path = vec![(node, 0)];
+        on_path[node.0] = true;
+        while let Some((id, next_dependency)) = path.last_mut() {
+            let current = &self.nodes[id.0];
+            if let Some(&dependency) = current.dependencies.get(*next_dependency) {
+                *next_dependency += 1;
+                if on_path[dependency.0] {
+                    return Err(CycleError { node: dependency });
+                }
+                if !visited[dependency.0] && !self.nodes[dependency.0].cell.is_initialized_relaxed()
+                {
+                    on_path[dependency.0] = true;
+                    path.push((dependency, 0));
+                }
+                continue;
+            }
+            on_path[id.0] = false;
+            visited[id.0] = true;
+            order.push(*id);
+            path.pop();
+        }
+        Ok(order)
+    }
+
+    /// Initializes `node`, whose dependencies must be initialized.
+    fn initialize(&self, node: NodeId) {
+        let current = &self.nodes[node.0];
+        let dependencies: Vec<&'a T> = current
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                self.get(*dependency)
+                    .expect("dependencies are initialized first")
+            })
+            .collect();
+        let _ = current.cell.get_or_init(|| (current.init)(&dependencies));
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, T: Sync> LazyGraph<'a, T> {
+    /// Like [`resolve`](Self::resolve), but initializes the nodes not
+    /// depending on each other concurrently, on up to `parallelism` threads
+    /// including the current one.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Cycle`] if an uninitialized node transitively depends
+    /// on itself.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the node doesn't belong to this graph, or if an initializer
+    /// panics.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::num::NonZeroUsize;
+    ///
+    /// use lazy_ref::lazy_graph::LazyGraph;
+    ///
+    /// let arena: Vec<u64> = (0..=80).collect();
+    /// let arena = &arena;
+    ///
+    /// let mut graph = LazyGraph::new();
+    /// let components: Vec<_> = (0..80).map(|i| graph.add_node(move |_| &arena[i])).collect();
+    /// let service = graph.add_node(|deps| &arena[deps.len()]);
+    /// for component in components {
+    ///     graph.add_dependency(service, component);
+    /// }
+    ///
+    /// let parallelism = NonZeroUsize::new(4).unwrap();
+    /// assert_eq!(graph.resolve_concurrent(service, parallelism), Ok(&80));
+    /// ```
+    pub fn resolve_concurrent(
+        &self,
+        node: NodeId,
+        parallelism: NonZeroUsize,
+    ) -> Result<&'a T, Error> {
+        if let Some(r) = self.get(node) {
+            return Ok(r);
+        }
+        let pending = self.pending(node)?;
+        let mut positions = vec![None; self.nodes.len()];
+        for (position, id) in pending.iter().enumerate() {
+            positions[id.0] = Some(position);
+        }
+        let mut blockers = vec![0_usize; pending.len()];
+        let mut dependents = vec![Vec::new(); pending.len()];
+        for (position, id) in pending.iter().enumerate() {
+            for dependency in &self.nodes[id.0].dependencies {
+                if let Some(dependency) = positions[dependency.0] {
+                    blockers[position] += 1;
+                    dependents[dependency].push(position);
+                }
+            }
+        }
+        let schedule = Schedule {
+            state: Mutex::new(ScheduleState {
+                ready: (0..pending.len())
+                    .filter(|&position| blockers[position] == 0)
+                    .collect(),
+                blockers,
+                left: pending.len(),
+                aborted: false,
+            }),
+            changed: Condvar::new(),
+        };
+        let work = || self.work(&pending, &dependents, &schedule);
+        thread::scope(|s| {
+            for _ in 1..parallelism.get().min(pending.len()) {
+                s.spawn(work);
+            }
+            work();
+        });
+        Ok(self.get(node).expect("the node has just been initialized"))
+    }
+
+    /// Initializes the nodes of `pending` as they become ready, until all of
+    /// them are initialized.
+    fn work(&self, pending: &[NodeId], dependents: &[Vec<usize>], schedule: &Schedule) {
+        let mut state = schedule.lock();
+        while !state.aborted && state.left != 0 {
+            let Some(position) = state.ready.pop() else {
+                state = schedule
+                    .changed
+                    .wait(state)
+                    .unwrap_or_else(PoisonError::into_inner);
+                continue;
+            };
+            drop(state);
+            let abort = AbortOnPanic(schedule);
+            self.initialize(pending[position]);
+            core::mem::forget(abort);
+            state = schedule.lock();
+            state.left -= 1;
+            for &dependent in &dependents[position] {
+                state.blockers[dependent] -= 1;
+                if state.blockers[dependent] == 0 {
+                    state.ready.push(dependent);
+                }
+            }
+            schedule.changed.notify_all();
+        }
+    }
+}
+
+/// The progress of [`LazyGraph::resolve_concurrent`] shared by its threads.
+#[cfg(feature = "std")]
+struct Schedule {
+    state: Mutex<ScheduleState>,
+    changed: Condvar,
+}
+
+#[cfg(feature = "std")]
+struct ScheduleState {
+    /// The positions of the pending nodes whose dependencies are initialized.
+    ready: Vec<usize>,
+    /// The numbers of the uninitialized pending dependencies of the pending
+    /// nodes.
+    blockers: Vec<usize>,
+    /// The number of the uninitialized pending nodes.
+    left: usize,
+    /// Whether an initializer has panicked.
+    aborted: bool,
+}
+
+#[cfg(feature = "std")]
+impl Schedule {
+    /// Locks the state, ignoring poisoning.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, ScheduleState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Stops the other threads resolving a [`LazyGraph`] when dropped by a
+/// panicking initializer.
+#[cfg(feature = "std")]
+struct AbortOnPanic<'s>(&'s Schedule);
+
+#[cfg(feature = "std")]
+impl Drop for AbortOnPanic<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        self.0.lock().aborted = true;
+        self.0.changed.notify_all();
+    }
+}
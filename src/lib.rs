@@ -1,14 +1,189 @@
 //! Implements a non-blocking synchronization primitive for lazy-initialized
 //! immutable references.
+//!
+//! # Features
+//!
+//! The crate is `no_std` and split into the following tiers:
+//!
+//! - without any features, only the cells that need neither allocation nor the
+//!   standard library are available;
+//! - `alloc` adds the types backed by heap allocations, such as [`lazy_graph`];
+//! - `std`, enabled by default, adds the types relying on the standard library,
+//!   such as threads, locks or time, and implies `alloc`.
+//!
+//! The `parking_lot` feature adds `LazyRef::wait`, blocking until the cell
+//! is initialized without growing it beyond a single word.
+//!
+//! The `metrics` feature makes [`LazyRefSeq`], [`LazyFn`], [`HandleMap`] and
+//! [`LazyRefCache`] report their initializations through the `metrics`
+//! facade, labelled with the `container` name: the
+//! `lazy_ref_init_duration_seconds` histogram of the time spent computing
+//! entries, and the `lazy_ref_initialized_total` and
+//! `lazy_ref_init_races_total` counters of the published and the discarded
+//! entries.
+//!
+//! The `pyo3` feature adds `LazyPyRef`, owning a lazily computed Python
+//! object.
+//!
+//! The `audit_overwrites` feature makes [`LazyRef::set`] panic instead of
+//! overwriting an initialized cell, to find the unintended overwrites.
+//! [`LazyRef::force_set`] keeps overwriting.
+//!
+//! The `testing` feature adds the `testing` module of helpers for the tests
+//! of the downstream crates.
 
-use std::{
+#![no_std]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::{
+    alloc::Layout,
     fmt::{Debug, Formatter},
     marker::PhantomData,
+    ptr::NonNull,
     sync::atomic::{AtomicPtr, Ordering},
 };
 
 use crossbeam_utils::atomic::AtomicConsume;
 
+#[cfg(feature = "either")]
+pub use crate::either::LazyEitherRef;
+#[cfg(feature = "rayon")]
+pub use crate::par::{par_init_all, par_init_keys};
+#[cfg(feature = "pyo3")]
+pub use crate::python::LazyPyRef;
+pub use crate::{
+    any::{LazyAnyRef, TypedAnyRef},
+    arena::ArenaAlloc,
+    backoff::{Backoff, ConstBackoff, SpinBackoff},
+    cache::LazyRefCache,
+    claim::{ClaimableLazyRef, InitGuard, InitState},
+    container::LazyContainer,
+    entry::VacantEntry,
+    error::Error,
+    first_n::FirstN,
+    keyed::KeyedLazyRef,
+    lazy_get::LazyGet,
+    maybe::MaybeLazyRef,
+    maybe_init::MaybeInit,
+    or::LazyRefOr,
+    pin::LazyPin,
+    queue::LazyRefQueue,
+    ranked::RankedLazyRef,
+    relative::RelativeLazyRef,
+    ring::RefRing,
+    shm::ShmLazyRef,
+    slice::{IterInitialized, LazyRefSliceExt},
+    snapshot::{snapshot_iter, SnapshotIter},
+    stable::StableRef,
+    stamped::StampedLazyRef,
+};
+#[cfg(feature = "std")]
+pub use crate::{
+    broadcast::{BroadcastRef, BroadcastToken},
+    futex::FutexLazyRef,
+    guarded::{guarded_scope, GuardedLazyRef, GuardedScope},
+    hash_cons::{ConsId, HashConsing},
+    interner::RefInterner,
+    lazy_fn::LazyFn,
+    publisher::ScopePublisher,
+    race::{set_init_race_hook, InitRace},
+    result::{LazyResultRef, RetryPolicy},
+    str_map::LazyStrMap,
+    type_map::LazyTypeMap,
+    warmup::{WaitAllInitialized, WarmupSet},
+};
+#[cfg(feature = "alloc")]
+pub use crate::{
+    handle_map::{Handle, HandleMap},
+    seq::{LazyRefSeq, Resolver},
+    small_vec::SmallLazyRefVec,
+    snapshot::{snapshot, Snapshot},
+};
+
+mod any;
+mod arena;
+mod backoff;
+#[cfg(feature = "std")]
+mod broadcast;
+mod cache;
+mod claim;
+mod container;
+#[cfg(feature = "either")]
+mod either;
+mod entry;
+mod error;
+mod first_n;
+#[cfg(feature = "std")]
+mod futex;
+#[cfg(feature = "std")]
+mod guarded;
+#[cfg(feature = "alloc")]
+mod handle_map;
+#[cfg(feature = "std")]
+mod hash_cons;
+#[cfg(feature = "std")]
+mod interner;
+mod keyed;
+#[cfg(feature = "std")]
+mod lazy_fn;
+mod lazy_get;
+#[cfg(feature = "alloc")]
+pub mod lazy_graph;
+#[cfg(feature = "std")]
+mod map;
+mod maybe;
+mod maybe_init;
+mod or;
+#[cfg(feature = "rayon")]
+mod par;
+#[cfg(feature = "parking_lot")]
+mod parking;
+mod pin;
+#[cfg(feature = "std")]
+mod publisher;
+#[cfg(feature = "pyo3")]
+mod python;
+mod queue;
+#[cfg(feature = "std")]
+mod race;
+mod ranked;
+#[cfg(all(debug_assertions, feature = "std"))]
+mod reentrancy;
+mod relative;
+#[cfg(feature = "std")]
+mod result;
+mod ring;
+#[cfg(feature = "std")]
+pub mod scoped;
+#[cfg(feature = "alloc")]
+mod seq;
+mod shm;
+mod slice;
+#[cfg(feature = "alloc")]
+mod small_vec;
+mod snapshot;
+mod stable;
+mod stamped;
+#[cfg(feature = "std")]
+mod str_map;
+mod table;
+#[cfg(any(feature = "either", feature = "std"))]
+mod tagged;
+mod telemetry;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+mod type_map;
+mod variance;
+#[cfg(feature = "tokio")]
+pub mod warm;
+#[cfg(feature = "std")]
+mod warmup;
+
 /// A non-blocking synchronization primitive (cell) for lazy-initialized
 /// immutable references.
 ///
@@ -152,12 +327,26 @@ use crossbeam_utils::atomic::AtomicConsume;
 ///     value
 /// }
 /// ```
+///
+/// # Layout
+///
+/// The cell has the same size, alignment and ABI as `*mut T`, holding a null
+/// pointer while empty. This is a stability guarantee, so the cell can be
+/// embedded in `#[repr(C)]` structs shared with foreign code or placed in
+/// memory-mapped headers, and checked with [`LAYOUT`](Self::LAYOUT).
 #[repr(transparent)]
 pub struct LazyRef<'a, T> {
     ptr: AtomicPtr<T>,
     _phantom: PhantomData<VarianceMarker<'a, T>>,
 }
 
+const _: () = {
+    assert!(LazyRef::<'static, u8>::LAYOUT.size() == size_of::<*mut u8>());
+    assert!(LazyRef::<'static, u8>::LAYOUT.align() == align_of::<*mut u8>());
+    assert!(LazyRef::<'static, u64>::LAYOUT.size() == size_of::<*mut u64>());
+    assert!(LazyRef::<'static, u64>::LAYOUT.align() == align_of::<*mut u64>());
+};
+
 /// Asserts invariance over `'a`, covariance over `T`.
 type VarianceMarker<'a, T> = fn(&'a ()) -> &'a T;
 
@@ -179,7 +368,7 @@ impl<T: Eq> Eq for LazyRef<'_, T> {}
 
 impl<T: Debug> Debug for LazyRef<'_, T> {
     #[inline]
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let mut d = f.debug_tuple("LazyRef");
         match self.get() {
             Some(v) => d.field(v),
@@ -196,27 +385,239 @@ impl<T> Default for LazyRef<'_, T> {
     }
 }
 
+/// A zeroed `LazyRef` is an empty cell.
+///
+/// ```rust
+/// use bytemuck::Zeroable;
+/// use lazy_ref::LazyRef;
+///
+/// let cells: [LazyRef<'_, usize>; 16] = Zeroable::zeroed();
+/// assert!(cells.iter().all(|cell| !cell.is_initialized()));
+/// ```
+#[cfg(feature = "bytemuck")]
+// SAFETY:
+// This is safe because the all-zeroes bit pattern is a null pointer, which
+// represents an empty cell.
+unsafe impl<T> bytemuck::Zeroable for LazyRef<'_, T> {}
+
+/// Generates either an empty cell or a cell initialized with a leaked
+/// arbitrary value.
+///
+/// ```rust
+/// use arbitrary::{Arbitrary, Unstructured};
+/// use lazy_ref::LazyRef;
+///
+/// #[derive(Arbitrary, Debug)]
+/// struct Node {
+///     id: u32,
+///     parent: LazyRef<'static, u32>,
+/// }
+///
+/// let mut u = Unstructured::new(&[7, 0, 0, 0, 1, 42, 0, 0, 0]);
+/// let node = Node::arbitrary(&mut u).unwrap();
+/// assert_eq!(node.id, 7);
+/// assert_eq!(node.parent.get(), Some(&42));
+/// ```
+#[cfg(feature = "arbitrary")]
+impl<'u, T: arbitrary::Arbitrary<'u> + 'static> arbitrary::Arbitrary<'u> for LazyRef<'static, T> {
+    #[inline]
+    fn arbitrary(u: &mut arbitrary::Unstructured<'u>) -> arbitrary::Result<Self> {
+        Ok(Option::<T>::arbitrary(u)?.map_or_else(Self::new, |value| {
+            Self::new_initialized(alloc::boxed::Box::leak(alloc::boxed::Box::new(value)))
+        }))
+    }
+
+    #[inline]
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        Option::<T>::size_hint(depth)
+    }
+}
+
 impl<'a, T> LazyRef<'a, T> {
+    /// The layout of the cell, the same as of `*mut T`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::alloc::Layout;
+    ///
+    /// use lazy_ref::LazyRef;
+    ///
+    /// const _: () = assert!(LazyRef::<'static, u64>::LAYOUT.size() == size_of::<*mut u64>());
+    /// assert_eq!(LazyRef::<'static, u64>::LAYOUT, Layout::new::<*mut u64>());
+    /// ```
+    pub const LAYOUT: Layout = Layout::new::<Self>();
+
     /// Creates a new empty cell.
     #[inline]
     #[must_use]
     pub const fn new() -> Self {
         Self {
-            ptr: AtomicPtr::new(std::ptr::null_mut()),
+            ptr: AtomicPtr::new(core::ptr::null_mut()),
             _phantom: PhantomData,
         }
     }
 
+    /// Creates an array of `N` new empty cells.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// static TABLE: [LazyRef<'static, usize>; 4] = LazyRef::new_uninit_array();
+    ///
+    /// static VALUE: usize = 1;
+    /// TABLE[2].set(&VALUE);
+    /// assert_eq!(TABLE[2].get(), Some(&1));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new_uninit_array<const N: usize>() -> [Self; N] {
+        [const { Self::new() }; N]
+    }
+
     /// Creates a new initialized cell.
     #[inline]
     #[must_use]
     pub const fn new_initialized(r: &'a T) -> Self {
         Self {
-            ptr: AtomicPtr::new(std::ptr::from_ref(r).cast_mut()),
+            ptr: AtomicPtr::new(core::ptr::from_ref(r).cast_mut()),
             _phantom: PhantomData,
         }
     }
 
+    /// Creates an array of cells initialized with `refs`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// static LIMITS: [LazyRef<'static, u32>; 2] = LazyRef::new_initialized_array([&16, &64]);
+    /// assert_eq!(LIMITS[1].get(), Some(&64));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized_array<const N: usize>(refs: [&'a T; N]) -> [Self; N] {
+        let mut cells = Self::new_uninit_array();
+        let mut idx = 0;
+        while idx < N {
+            cells[idx] = Self::new_initialized(refs[idx]);
+            idx += 1;
+        }
+        cells
+    }
+
+    /// Creates an array of cells, initializing the ones given `Some`
+    /// reference and leaving the rest empty.
+    ///
+    /// The arrays of [`lazy_ref_table!`] are built with it.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// static FIXED: u32 = 1;
+    /// static TABLE: [LazyRef<'static, u32>; 2] = LazyRef::new_table([Some(&FIXED), None]);
+    /// assert_eq!(TABLE[0].get(), Some(&1));
+    /// assert!(!TABLE[1].is_initialized());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const fn new_table<const N: usize>(refs: [Option<&'a T>; N]) -> [Self; N] {
+        let mut cells = Self::new_uninit_array();
+        let mut idx = 0;
+        while idx < N {
+            if let Some(r) = refs[idx] {
+                cells[idx] = Self::new_initialized(r);
+            }
+            idx += 1;
+        }
+        cells
+    }
+
+    /// Erases the lifetime of the cell, extending it to `'static`.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`transmute_lifetime`](Self::transmute_lifetime) with
+    /// `'b` being `'static`.
+    #[inline]
+    #[must_use]
+    pub const unsafe fn erase_lifetime(self) -> LazyRef<'static, T> {
+        // SAFETY:
+        // This is safe because the caller upholds the safety contract of
+        // `transmute_lifetime`.
+        unsafe { self.transmute_lifetime() }
+    }
+
+    /// Changes the lifetime of the cell to an arbitrary `'b`, which is useful
+    /// for arenas managing lifetimes dynamically.
+    ///
+    /// # Safety
+    ///
+    /// The caller must ensure that the referenced value stays valid as long
+    /// as the returned cell, or any reference taken out of it, is in use
+    /// during `'b`. The returned cell can be set with references living
+    /// for `'b` only, so if `'b` is longer than `'a`, the original arena
+    /// must not rely on the cell holding references living for `'a`.
+    #[inline]
+    #[must_use]
+    pub const unsafe fn transmute_lifetime<'b>(self) -> LazyRef<'b, T> {
+        LazyRef {
+            ptr: AtomicPtr::new(self.ptr.into_inner()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Views `ptr` as a cell.
+    ///
+    /// # Safety
+    ///
+    /// While the returned cell is in use, every pointer stored in `ptr` must
+    /// be either null or created from a reference to a value that stays valid
+    /// and immutable for `'a`, and every non-null pointer must be stored with
+    /// at least release ordering.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::atomic::{AtomicPtr, Ordering};
+    ///
+    /// use lazy_ref::LazyRef;
+    ///
+    /// static VALUE: u32 = 42;
+    /// let raw = AtomicPtr::new(std::ptr::null_mut());
+    ///
+    /// // SAFETY: only `&'static u32` references are stored in `raw`.
+    /// let cell: &LazyRef<'static, u32> = unsafe { LazyRef::from_atomic_ptr_ref(&raw) };
+    /// assert_eq!(cell.get_or_init(|| &VALUE), &42);
+    /// assert_eq!(raw.load(Ordering::Acquire).cast_const(), &VALUE as *const u32);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub const unsafe fn from_atomic_ptr_ref(ptr: &AtomicPtr<T>) -> &Self {
+        // SAFETY:
+        // This is safe because the cell is a transparent wrapper around `AtomicPtr<T>`,
+        // and the caller guarantees that the pointers stored in it uphold its
+        // invariant.
+        unsafe { &*core::ptr::from_ref(ptr).cast::<Self>() }
+    }
+
+    /// Returns the underlying atomic pointer.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`from_atomic_ptr_ref`](Self::from_atomic_ptr_ref) for
+    /// the pointers stored through the returned reference.
+    #[inline]
+    #[must_use]
+    pub const unsafe fn as_atomic(&self) -> &AtomicPtr<T> {
+        &self.ptr
+    }
+
     /// Gets the underlying reference.
     ///
     /// Returns `None` if the cell is empty.
@@ -224,10 +625,51 @@ impl<'a, T> LazyRef<'a, T> {
     #[must_use]
     pub fn get(&self) -> Option<&'a T> {
         let ptr = self.ptr.load_consume();
+        if ptr.is_null() {
+            cold_path();
+            return None;
+        }
         // SAFETY:
         // This is safe because this pointer can only be created from a valid reference,
-        // or it is null.
-        unsafe { ptr.as_ref() }
+        // and it is not null.
+        Some(unsafe { &*ptr })
+    }
+
+    /// Gets the underlying reference, for the code treating an empty cell as
+    /// a recoverable error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Uninitialized`] if the cell is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::{Error, LazyRef};
+    ///
+    /// fn port(cell: &LazyRef<'_, u16>) -> Result<u16, Error> {
+    ///     Ok(*cell.try_get()? + 1)
+    /// }
+    ///
+    /// let base = 8080;
+    /// let cell = LazyRef::new();
+    /// assert_eq!(port(&cell), Err(Error::Uninitialized));
+    /// cell.set(&base);
+    /// assert_eq!(port(&cell), Ok(8081));
+    /// ```
+    #[inline]
+    pub fn try_get(&self) -> Result<&'a T, Error> {
+        self.get().ok_or(Error::Uninitialized)
+    }
+
+    /// Gets the underlying reference as a [`NonNull`] pointer, which is valid
+    /// for reads for `'a`.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get_nonnull(&self) -> Option<NonNull<T>> {
+        NonNull::new(self.ptr.load_consume())
     }
 
     /// Gets the underlying reference of the cell, initializing it with `f` if
@@ -235,16 +677,163 @@ impl<'a, T> LazyRef<'a, T> {
     ///
     /// Many threads may call `get_or_init` concurrently with different
     /// initializing functions. In this case multiple functions can be
-    /// executed.
+    /// executed, but the initializer never overwrites a reference that was
+    /// published in the meantime. Such a reference is returned instead of the
+    /// one produced by `f`. In debug builds with the `std` feature, such lost
+    /// races are reported to the hook set with [`set_init_race_hook`].
+    ///
+    /// # Panics
+    ///
+    /// In debug builds with the `std` feature, panics if `f` tries to
+    /// initialize the same cell reentrantly.
     #[inline]
     #[must_use]
     pub fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
-        self.get().unwrap_or_else(|| {
-            let r = f();
-            self.ptr
-                .store(std::ptr::from_ref(r).cast_mut(), Ordering::Release);
-            r
-        })
+        match self.get() {
+            Some(r) => r,
+            None => self.initialize(f),
+        }
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with
+    /// `f(ctx)` if the cell was empty.
+    ///
+    /// Behaves like [`get_or_init`](Self::get_or_init), but the initializer is
+    /// a plain function pointer given its context explicitly, so that the
+    /// call sites sharing the same context type share the same instance of
+    /// this method instead of each instantiating it with its own closure.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds with the `std` feature, panics if `f` tries to
+    /// initialize the same cell reentrantly.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// fn first(names: &[String]) -> &String {
+    ///     &names[0]
+    /// }
+    ///
+    /// let names = vec!["alice".to_string(), "bob".to_string()];
+    /// let cell = LazyRef::new();
+    ///
+    /// assert_eq!(cell.get_or_init_with(names.as_slice(), first), "alice");
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn get_or_init_with<C>(&self, ctx: C, f: fn(C) -> &'a T) -> &'a T {
+        match self.get() {
+            Some(r) => r,
+            None => self.initialize(|| f(ctx)),
+        }
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with the
+    /// value of the handle returned by `f` if the cell was empty.
+    ///
+    /// Behaves like [`get_or_init`](Self::get_or_init), but accepts any
+    /// [`StableRef`], such as a pinned reference or a handle into an arena.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init_from<R: StableRef<'a, Target = T>>(&self, f: impl FnOnce() -> R) -> &'a T {
+        self.get_or_init(|| f().into_ref())
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with a clone
+    /// of `src` moved into `arena` if the cell was empty.
+    ///
+    /// Behaves like [`get_or_init`](Self::get_or_init), so `src` can be
+    /// cloned into the arena by multiple threads concurrently, but only one
+    /// clone gets published.
+    #[inline]
+    #[must_use]
+    pub fn get_or_clone_into(&self, arena: &'a impl ArenaAlloc<T>, src: &T) -> &'a T
+    where
+        T: Clone,
+    {
+        self.get_or_init(|| arena.alloc(src.clone()))
+    }
+
+    /// Returns a [`VacantEntry`] to fill the cell with, or `None` if the cell
+    /// is initialized.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// let (first, second) = (1, 2);
+    /// let cell = LazyRef::new();
+    ///
+    /// // Dropping the entry abandons it.
+    /// drop(cell.vacant());
+    /// assert!(!cell.is_initialized());
+    ///
+    /// if let Some(entry) = cell.vacant() {
+    ///     assert_eq!(entry.publish(&first), &1);
+    /// }
+    /// assert!(cell.vacant().is_none());
+    ///
+    /// // Entries taken before the cell got initialized don't overwrite it.
+    /// let cell = LazyRef::new();
+    /// let (a, b) = (cell.vacant().unwrap(), cell.vacant().unwrap());
+    /// assert_eq!(a.publish(&first), &1);
+    /// assert_eq!(b.publish(&second), &1);
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn vacant(&self) -> Option<VacantEntry<'_, 'a, T>> {
+        (!self.is_initialized()).then_some(VacantEntry { cell: self })
+    }
+
+    /// The slow path of [`get_or_init`](Self::get_or_init).
+    #[cold]
+    #[inline(never)]
+    fn initialize(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        let _guard = reentrancy::InitGuard::enter(core::ptr::from_ref(self).addr());
+        match self.try_publish(f()) {
+            Ok(r) => r,
+            Err(published) => {
+                #[cfg(all(debug_assertions, feature = "std"))]
+                race::report(core::ptr::from_ref(self).addr());
+                published
+            }
+        }
+    }
+
+    /// Publishes `r` unless the cell is initialized, returning the published
+    /// reference.
+    #[inline]
+    fn publish_if_empty(&self, r: &'a T) -> &'a T {
+        match self.try_publish(r) {
+            Ok(r) | Err(r) => r,
+        }
+    }
+
+    /// Publishes `r` unless the cell is initialized.
+    ///
+    /// Returns the reference published before if the cell was initialized.
+    #[inline]
+    fn try_publish(&self, r: &'a T) -> Result<&'a T, &'a T> {
+        match self.ptr.compare_exchange(
+            core::ptr::null_mut(),
+            core::ptr::from_ref(r).cast_mut(),
+            Ordering::Release,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {
+                self.published();
+                Ok(r)
+            }
+            // SAFETY:
+            // This is safe because this pointer can only be created from a valid reference,
+            // and it is not null since the exchange failed.
+            Err(ptr) => Err(unsafe { &*ptr }),
+        }
     }
 
     /// Gets the underlying reference. It doesn't introduce any overhead
@@ -266,8 +855,12 @@ impl<'a, T> LazyRef<'a, T> {
     /// Returns `None` if the cell was empty.
     #[inline]
     #[must_use]
-    pub fn into_inner(mut self) -> Option<&'a T> {
-        self.get_owned()
+    pub const fn into_inner(self) -> Option<&'a T> {
+        let ptr = self.ptr.into_inner();
+        // SAFETY:
+        // This is safe because this pointer can only be created from a valid reference,
+        // or it is null.
+        unsafe { ptr.as_ref() }
     }
 
     /// Checks whether the cell is initialized.
@@ -277,6 +870,19 @@ impl<'a, T> LazyRef<'a, T> {
         !self.ptr.load_consume().is_null()
     }
 
+    /// Checks whether the cell is initialized using a relaxed load, which is
+    /// cheaper than the one of [`is_initialized`](Self::is_initialized) on
+    /// weakly ordered architectures.
+    ///
+    /// The check doesn't synchronize with the thread that initialized the
+    /// cell, so accessing the reference still requires [`get`](Self::get).
+    /// Suitable for scanning many cells, e.g. to report warm-up progress.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized_relaxed(&self) -> bool {
+        !self.ptr.load(Ordering::Relaxed).is_null()
+    }
+
     /// Checks whether the cell is initialized. It doesn't introduce any
     /// overhead compared to the [`is_initialized`](Self::is_initialized)
     /// method, but is only available through unique access.
@@ -287,10 +893,74 @@ impl<'a, T> LazyRef<'a, T> {
     }
 
     /// Sets the contents of this cell to `r`.
+    ///
+    /// Overwrites the reference the cell may already hold. Use
+    /// [`set_if_empty`](Self::set_if_empty) to avoid this.
+    ///
+    /// # Panics
+    ///
+    /// With the `audit_overwrites` feature, panics if the cell is initialized,
+    /// leaving it intact. Overwrite with [`force_set`](Self::force_set) where
+    /// it is intended.
     #[inline]
     pub fn set(&self, r: &'a T) {
+        #[cfg(feature = "audit_overwrites")]
+        assert!(
+            self.set_if_empty(r),
+            "`LazyRef::set` tried to overwrite an initialized cell; use `force_set` if this is intended"
+        );
+        #[cfg(not(feature = "audit_overwrites"))]
+        self.force_set(r);
+    }
+
+    /// Sets the contents of this cell to `r` only if the cell is empty.
+    ///
+    /// Returns `true` if the cell was empty and `r` has been published.
+    #[inline]
+    pub fn set_if_empty(&self, r: &'a T) -> bool {
+        let published = self
+            .ptr
+            .compare_exchange(
+                core::ptr::null_mut(),
+                core::ptr::from_ref(r).cast_mut(),
+                Ordering::Release,
+                Ordering::Relaxed,
+            )
+            .is_ok();
+        if published {
+            self.published();
+        }
+        published
+    }
+
+    /// Sets the contents of this cell to `r` only if the cell is empty.
+    ///
+    /// Does the same as [`set_if_empty`](Self::set_if_empty), but reports
+    /// the initialized cell as an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::AlreadyInitialized`] if the cell is already
+    /// initialized, in which case it is left as is.
+    #[inline]
+    pub fn try_set(&self, r: &'a T) -> Result<(), Error> {
+        if self.set_if_empty(r) {
+            Ok(())
+        } else {
+            Err(Error::AlreadyInitialized)
+        }
+    }
+
+    /// Sets the contents of this cell to `r`, overwriting the reference it
+    /// may already hold.
+    ///
+    /// Does the same as [`set`](Self::set), but makes the intent to overwrite
+    /// explicit, so it never panics with the `audit_overwrites` feature.
+    #[inline]
+    pub fn force_set(&self, r: &'a T) {
         self.ptr
-            .store(std::ptr::from_ref(r).cast_mut(), Ordering::Release);
+            .store(core::ptr::from_ref(r).cast_mut(), Ordering::Release);
+        self.published();
     }
 
     /// Sets the contents of this cell to `r`. It doesn't introduce any overhead
@@ -298,6 +968,120 @@ impl<'a, T> LazyRef<'a, T> {
     /// through unique access.
     #[inline]
     pub fn set_owned(&mut self, r: &'a T) {
-        *self.ptr.get_mut() = std::ptr::from_ref(r).cast_mut();
+        *self.ptr.get_mut() = core::ptr::from_ref(r).cast_mut();
+    }
+
+    /// Hints the CPU to prefetch the referenced value into the cache, so that
+    /// a subsequent read of it is faster.
+    ///
+    /// Does nothing if the cell is empty or the target architecture doesn't
+    /// support prefetching.
+    #[inline]
+    pub fn prefetch(&self) {
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        if !ptr.is_null() {
+            prefetch(ptr);
+        }
+    }
+
+    /// Takes the underlying reference out of the cell, leaving it empty. It
+    /// doesn't perform any atomic operations, but is only available through
+    /// unique access.
+    ///
+    /// Returns `None` if the cell was empty.
+    #[inline]
+    pub fn take_owned(&mut self) -> Option<&'a T> {
+        let ptr = core::mem::replace(self.ptr.get_mut(), core::ptr::null_mut());
+        // SAFETY:
+        // This is safe because this pointer can only be created from a valid reference,
+        // or it is null.
+        unsafe { ptr.as_ref() }
+    }
+
+    /// Empties the cell. It doesn't perform any atomic operations, but is
+    /// only available through unique access.
+    #[inline]
+    pub fn reset_owned(&mut self) {
+        *self.ptr.get_mut() = core::ptr::null_mut();
+    }
+
+    /// Swaps the contents of this cell with the contents of `other`.
+    ///
+    /// The swap consists of two independent atomic operations and is not
+    /// atomic as a whole: a reference published concurrently to either of
+    /// the cells may be lost.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// let (current, previous) = (1, 0);
+    /// let current_cell = LazyRef::new_initialized(&current);
+    /// let previous_cell = LazyRef::new_initialized(&previous);
+    ///
+    /// current_cell.swap(&previous_cell);
+    /// assert_eq!(current_cell.get(), Some(&0));
+    /// assert_eq!(previous_cell.get(), Some(&1));
+    /// ```
+    #[inline]
+    pub fn swap(&self, other: &Self) {
+        if core::ptr::eq(self, other) {
+            return;
+        }
+        let ours = self.ptr.load(Ordering::Acquire);
+        let theirs = other.ptr.swap(ours, Ordering::AcqRel);
+        self.ptr.store(theirs, Ordering::Release);
+        self.published();
+        other.published();
+    }
+
+    /// Wakes the threads waiting for the cell to be initialized with the
+    /// `parking_lot` feature.
+    #[inline]
+    #[cfg_attr(not(feature = "parking_lot"), allow(clippy::unused_self))]
+    fn published(&self) {
+        #[cfg(feature = "parking_lot")]
+        self.unpark_waiters();
+    }
+}
+
+/// Marks the code path calling it as unlikely to be taken.
+#[cold]
+#[inline(always)]
+fn cold_path() {}
+
+/// Hints the CPU to prefetch the memory at `ptr` into the cache.
+#[inline]
+fn prefetch<T>(ptr: *const T) {
+    #[cfg(all(target_arch = "x86_64", target_feature = "sse"))]
+    // SAFETY:
+    // This is safe because the `sse` target feature is enabled, and prefetching never
+    // faults.
+    unsafe {
+        core::arch::x86_64::_mm_prefetch::<{ core::arch::x86_64::_MM_HINT_T0 }>(ptr.cast());
+    }
+    #[cfg(all(target_arch = "x86", target_feature = "sse"))]
+    // SAFETY:
+    // This is safe because the `sse` target feature is enabled, and prefetching never
+    // faults.
+    unsafe {
+        core::arch::x86::_mm_prefetch::<{ core::arch::x86::_MM_HINT_T0 }>(ptr.cast());
+    }
+    #[cfg(target_arch = "aarch64")]
+    // SAFETY:
+    // This is safe because prefetching doesn't modify the memory and never faults.
+    unsafe {
+        core::arch::asm!(
+            "prfm pldl1keep, [{ptr}]",
+            ptr = in(reg) ptr,
+            options(nostack, preserves_flags, readonly),
+        );
     }
+    #[cfg(not(any(
+        all(target_arch = "x86_64", target_feature = "sse"),
+        all(target_arch = "x86", target_feature = "sse"),
+        target_arch = "aarch64",
+    )))]
+    let _ = ptr;
 }
@@ -0,0 +1,304 @@
+//! A lock-free insert-only hash map backing the crate's memoizing types.
+//!
+//! The map is a hash trie: every branch node dispatches on `BITS` bits of the
+//! key hash, and every slot of a branch is either empty, a leaf or another
+//! branch. Entries are never removed or moved to another allocation until the
+//! map is dropped, so references to keys and values remain valid for the
+//! lifetime of the map.
+
+use std::{
+    borrow::Borrow,
+    boxed::Box,
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, Hash, RandomState},
+    marker::PhantomData,
+    sync::atomic::{AtomicPtr, AtomicUsize, Ordering},
+};
+
+const BITS: u32 = 4;
+const WIDTH: usize = 1 << BITS;
+
+enum Node<K, V> {
+    Leaf(Leaf<K, V>),
+    Branch(Branch<K, V>),
+}
+
+struct Leaf<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+    /// The next leaf with the same hash.
+    next: AtomicPtr<Node<K, V>>,
+}
+
+struct Branch<K, V> {
+    slots: [AtomicPtr<Node<K, V>>; WIDTH],
+}
+
+impl<K, V> Branch<K, V> {
+    const fn new() -> Self {
+        Self {
+            slots: [const { AtomicPtr::new(std::ptr::null_mut()) }; WIDTH],
+        }
+    }
+
+    #[inline]
+    fn slot(&self, hash: u64, depth: u32) -> &AtomicPtr<Node<K, V>> {
+        // The truncation is intended: only `BITS` bits are taken.
+        #[allow(clippy::cast_possible_truncation)]
+        let idx = (hash >> (depth * BITS)) as usize & (WIDTH - 1);
+        &self.slots[idx]
+    }
+}
+
+/// A lock-free insert-only hash map.
+pub(crate) struct RawMap<K, V, S = RandomState> {
+    root: Branch<K, V>,
+    len: AtomicUsize,
+    hasher: S,
+    _phantom: PhantomData<Box<Node<K, V>>>,
+}
+
+// SAFETY:
+// The map owns its keys and values, so it can be sent to another thread if they
+// can.
+unsafe impl<K: Send, V: Send, S: Send> Send for RawMap<K, V, S> {}
+
+// SAFETY:
+// Shared access allows inserting keys and values from any thread and getting
+// shared references to them, which requires them to be both `Send` and `Sync`.
+unsafe impl<K: Send + Sync, V: Send + Sync, S: Sync> Sync for RawMap<K, V, S> {}
+
+impl<K: Debug, V: Debug, S> Debug for RawMap<K, V, S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_map();
+        self.for_each(|key, value| {
+            d.entry(key, value);
+        });
+        d.finish()
+    }
+}
+
+impl<K, V, S> Drop for RawMap<K, V, S> {
+    fn drop(&mut self) {
+        fn drop_branch<K, V>(branch: &mut Branch<K, V>) {
+            for slot in &mut branch.slots {
+                let mut ptr = *slot.get_mut();
+                while !ptr.is_null() {
+                    // SAFETY:
+                    // This is safe because every non-null pointer in the map is created
+                    // from a `Box` and is owned by exactly one slot.
+                    let node = unsafe { Box::from_raw(ptr) };
+                    ptr = match *node {
+                        Node::Leaf(mut leaf) => *leaf.next.get_mut(),
+                        Node::Branch(mut branch) => {
+                            drop_branch(&mut branch);
+                            std::ptr::null_mut()
+                        }
+                    };
+                }
+            }
+        }
+        drop_branch(&mut self.root);
+    }
+}
+
+impl<K, V, S> RawMap<K, V, S> {
+    /// Creates a new empty map which will use the given hash builder.
+    #[inline]
+    pub(crate) const fn with_hasher(hasher: S) -> Self {
+        Self {
+            root: Branch::new(),
+            len: AtomicUsize::new(0),
+            hasher,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Returns the number of entries in the map.
+    #[inline]
+    pub(crate) fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    /// Calls `f` on every entry of the map in an unspecified order.
+    ///
+    /// Entries inserted concurrently may or may not be visited.
+    pub(crate) fn for_each<'m>(&'m self, mut f: impl FnMut(&'m K, &'m V)) {
+        fn visit<'m, K, V>(branch: &'m Branch<K, V>, f: &mut impl FnMut(&'m K, &'m V)) {
+            for slot in &branch.slots {
+                let mut ptr = slot.load(Ordering::Acquire);
+                // SAFETY:
+                // This is safe because every non-null pointer in the map points to a valid
+                // node, which is not deallocated until the map is dropped.
+                while let Some(node) = unsafe { ptr.as_ref() } {
+                    ptr = match node {
+                        Node::Leaf(leaf) => {
+                            f(&leaf.key, &leaf.value);
+                            leaf.next.load(Ordering::Acquire)
+                        }
+                        Node::Branch(branch) => {
+                            visit(branch, f);
+                            std::ptr::null_mut()
+                        }
+                    };
+                }
+            }
+        }
+        visit(&self.root, &mut f);
+    }
+}
+
+impl<K: Hash + Eq, V, S: BuildHasher> RawMap<K, V, S> {
+    /// Gets the entry corresponding to `key`.
+    #[inline]
+    pub(crate) fn get<Q>(&self, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let hash = self.hasher.hash_one(key);
+        let mut branch = &self.root;
+        let mut depth = 0;
+        loop {
+            let ptr = branch.slot(hash, depth).load(Ordering::Acquire);
+            // SAFETY:
+            // This is safe because every non-null pointer in the map points to a valid
+            // node, which is not deallocated until the map is dropped.
+            match unsafe { ptr.as_ref() }? {
+                Node::Leaf(leaf) => return leaf.find(hash, key),
+                Node::Branch(next) => {
+                    branch = next;
+                    depth += 1;
+                }
+            }
+        }
+    }
+
+    /// Gets the entry corresponding to `key`, inserting the value returned by
+    /// `f` if there is none.
+    ///
+    /// Many threads may call `get_or_insert_with` concurrently with the same
+    /// key. In this case multiple functions can be executed, but only one of
+    /// the entries is inserted and returned to every caller.
+    pub(crate) fn get_or_insert_with(&self, key: K, f: impl FnOnce(&K) -> V) -> (&K, &V) {
+        if let Some(entry) = self.get(&key) {
+            return entry;
+        }
+        let hash = self.hasher.hash_one(&key);
+        let value = f(&key);
+        let new = Box::into_raw(Box::new(Node::Leaf(Leaf {
+            hash,
+            key,
+            value,
+            next: AtomicPtr::new(std::ptr::null_mut()),
+        })));
+        // SAFETY:
+        // This is safe because `new` has just been created from a `Box`.
+        let Node::Leaf(new_leaf) = (unsafe { &*new }) else {
+            unreachable!()
+        };
+
+        let mut branch = &self.root;
+        let mut depth = 0;
+        let inserted = 'insert: loop {
+            let slot = branch.slot(hash, depth);
+            let current = slot.load(Ordering::Acquire);
+            // SAFETY:
+            // This is safe because every non-null pointer in the map points to a valid
+            // node, which is not deallocated until the map is dropped.
+            match unsafe { current.as_ref() } {
+                None => {
+                    if slot
+                        .compare_exchange(current, new, Ordering::AcqRel, Ordering::Acquire)
+                        .is_ok()
+                    {
+                        break 'insert None;
+                    }
+                }
+                Some(Node::Branch(next)) => {
+                    branch = next;
+                    depth += 1;
+                }
+                Some(Node::Leaf(first)) if first.hash == hash => {
+                    let mut leaf = first;
+                    loop {
+                        if leaf.key == new_leaf.key {
+                            break 'insert Some((&leaf.key, &leaf.value));
+                        }
+                        match leaf.next.compare_exchange(
+                            std::ptr::null_mut(),
+                            new,
+                            Ordering::AcqRel,
+                            Ordering::Acquire,
+                        ) {
+                            Ok(_) => break 'insert None,
+                            // SAFETY:
+                            // This is safe because the exchange failed, so `next` is a non-null
+                            // pointer in the map.
+                            Err(next) => match unsafe { &*next } {
+                                Node::Leaf(next) => leaf = next,
+                                Node::Branch(_) => unreachable!(),
+                            },
+                        }
+                    }
+                }
+                Some(Node::Leaf(leaf)) => {
+                    let split = Branch::new();
+                    split
+                        .slot(leaf.hash, depth + 1)
+                        .store(current, Ordering::Relaxed);
+                    let split = Box::into_raw(Box::new(Node::Branch(split)));
+                    if slot
+                        .compare_exchange(current, split, Ordering::AcqRel, Ordering::Acquire)
+                        .is_err()
+                    {
+                        // SAFETY:
+                        // This is safe because `split` has not been published. Dropping it
+                        // doesn't drop the nodes it points to.
+                        drop(unsafe { Box::from_raw(split) });
+                    }
+                }
+            }
+        };
+
+        if let Some(entry) = inserted {
+            // SAFETY:
+            // This is safe because `new` has not been published.
+            drop(unsafe { Box::from_raw(new) });
+            entry
+        } else {
+            self.len.fetch_add(1, Ordering::Relaxed);
+            (&new_leaf.key, &new_leaf.value)
+        }
+    }
+}
+
+impl<K, V> Leaf<K, V> {
+    /// Finds the entry corresponding to `key` among this leaf and the next
+    /// ones.
+    #[inline]
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<(&K, &V)>
+    where
+        K: Borrow<Q>,
+        Q: Eq + ?Sized,
+    {
+        if self.hash != hash {
+            return None;
+        }
+        let mut leaf = self;
+        loop {
+            if leaf.key.borrow() == key {
+                return Some((&leaf.key, &leaf.value));
+            }
+            // SAFETY:
+            // This is safe because every non-null pointer in the map points to a valid
+            // node, which is not deallocated until the map is dropped.
+            match unsafe { leaf.next.load(Ordering::Acquire).as_ref() }? {
+                Node::Leaf(next) => leaf = next,
+                Node::Branch(_) => unreachable!(),
+            }
+        }
+    }
+}
@@ -0,0 +1,204 @@
+use core::{
+    alloc::Layout,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crate::LazyRef;
+
+/// The byte whose address marks the absence of a cell.
+static NO_CELL: u8 = 0;
+
+/// A compact alternative to `Option<LazyRef<'a, T>>`, taking a single pointer
+/// word.
+///
+/// The absence of a cell is marked with the address of a private static,
+/// which no reference to a non-zero-sized `T` can have. Zero-sized types are
+/// rejected at compile time.
+///
+/// The presence of a cell can only be changed through unique access, so
+/// [`as_lazy_ref`](Self::as_lazy_ref) can hand out the cell itself.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, MaybeLazyRef};
+///
+/// assert_eq!(size_of::<MaybeLazyRef<'_, u32>>(), size_of::<LazyRef<'_, u32>>());
+///
+/// let value = 42;
+/// let mut maybe = MaybeLazyRef::none();
+/// assert!(maybe.as_lazy_ref().is_none());
+///
+/// maybe.insert_cell();
+/// let cell = maybe.as_lazy_ref().unwrap();
+/// assert_eq!(cell.get_or_init(|| &value), &42);
+/// assert_eq!(maybe.get(), Some(&42));
+/// ```
+///
+/// ```compile_fail
+/// use lazy_ref::MaybeLazyRef;
+///
+/// let _ = MaybeLazyRef::<()>::none();
+/// ```
+#[repr(transparent)]
+pub struct MaybeLazyRef<'a, T> {
+    inner: LazyRef<'a, T>,
+}
+
+impl<T> Clone for MaybeLazyRef<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        self.as_lazy_ref().cloned().into()
+    }
+}
+
+impl<T: PartialEq> PartialEq for MaybeLazyRef<'_, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.as_lazy_ref() == other.as_lazy_ref()
+    }
+}
+
+impl<T: Eq> Eq for MaybeLazyRef<'_, T> {}
+
+impl<T: Debug> Debug for MaybeLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("MaybeLazyRef");
+        match self.as_lazy_ref() {
+            Some(cell) => d.field(cell),
+            None => d.field(&format_args!("<none>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for MaybeLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::none()
+    }
+}
+
+impl<'a, T> From<Option<LazyRef<'a, T>>> for MaybeLazyRef<'a, T> {
+    #[inline]
+    fn from(cell: Option<LazyRef<'a, T>>) -> Self {
+        cell.map_or_else(Self::none, Self::some)
+    }
+}
+
+impl<'a, T> From<MaybeLazyRef<'a, T>> for Option<LazyRef<'a, T>> {
+    #[inline]
+    fn from(maybe: MaybeLazyRef<'a, T>) -> Self {
+        maybe.into_option()
+    }
+}
+
+const _: () = {
+    assert!(MaybeLazyRef::<'static, u64>::LAYOUT.size() == size_of::<*mut u64>());
+    assert!(MaybeLazyRef::<'static, u64>::LAYOUT.align() == align_of::<*mut u64>());
+};
+
+impl<'a, T> MaybeLazyRef<'a, T> {
+    /// The layout of the value, the same as of `*mut T`.
+    pub const LAYOUT: Layout = Layout::new::<Self>();
+
+    /// Creates a value without a cell.
+    #[inline]
+    #[must_use]
+    pub const fn none() -> Self {
+        Self {
+            inner: LazyRef {
+                ptr: AtomicPtr::new(Self::sentinel()),
+                _phantom: PhantomData,
+            },
+        }
+    }
+
+    /// Creates a value holding `cell`.
+    #[inline]
+    #[must_use]
+    pub const fn some(cell: LazyRef<'a, T>) -> Self {
+        Self::assert_not_zero_sized();
+        Self { inner: cell }
+    }
+
+    /// Checks whether there is a cell.
+    #[inline]
+    #[must_use]
+    pub fn is_some(&self) -> bool {
+        self.as_lazy_ref().is_some()
+    }
+
+    /// Checks whether there is no cell.
+    #[inline]
+    #[must_use]
+    pub fn is_none(&self) -> bool {
+        !self.is_some()
+    }
+
+    /// Returns the cell, or `None` if there is no cell.
+    #[inline]
+    #[must_use]
+    pub fn as_lazy_ref(&self) -> Option<&LazyRef<'a, T>> {
+        // The pointer is never set to or from the sentinel through shared access.
+        (self.inner.ptr.load(Ordering::Relaxed) != Self::sentinel()).then_some(&self.inner)
+    }
+
+    /// Gets the underlying reference of the cell.
+    ///
+    /// Returns `None` if there is no cell or the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.as_lazy_ref().and_then(LazyRef::get)
+    }
+
+    /// Inserts an empty cell if there is no cell, returning the cell.
+    #[inline]
+    pub fn insert_cell(&mut self) -> &LazyRef<'a, T> {
+        let ptr = self.inner.ptr.get_mut();
+        if *ptr == Self::sentinel() {
+            *ptr = core::ptr::null_mut();
+        }
+        &self.inner
+    }
+
+    /// Takes the cell out, leaving no cell in its place.
+    #[inline]
+    pub fn take(&mut self) -> Option<LazyRef<'a, T>> {
+        core::mem::take(self).into_option()
+    }
+
+    /// Converts into `Option<LazyRef<'a, T>>`.
+    #[inline]
+    #[must_use]
+    pub fn into_option(self) -> Option<LazyRef<'a, T>> {
+        let ptr = self.inner.ptr.into_inner();
+        (ptr != Self::sentinel()).then(|| LazyRef {
+            ptr: AtomicPtr::new(ptr),
+            _phantom: PhantomData,
+        })
+    }
+
+    /// Returns the pointer marking the absence of a cell.
+    #[inline]
+    const fn sentinel() -> *mut T {
+        Self::assert_not_zero_sized();
+        core::ptr::from_ref(&NO_CELL).cast_mut().cast()
+    }
+
+    /// Rejects zero-sized types at compile time, since references to them
+    /// may have any address.
+    #[inline]
+    const fn assert_not_zero_sized() {
+        const {
+            assert!(
+                size_of::<T>() != 0,
+                "`MaybeLazyRef` doesn't support zero-sized types"
+            );
+        };
+    }
+}
@@ -0,0 +1,197 @@
+use core::fmt::{Debug, Formatter};
+
+use crate::{LazyRef, StableRef};
+
+/// A reference that may not be initialized yet, for the APIs that return
+/// either the value or the cell it is going to be published to.
+///
+/// A pending reference keeps watching its cell: [`get`](Self::get) returns
+/// the value as soon as it is published, and [`refresh`](Self::refresh)
+/// makes a pending reference ready once it is.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, MaybeInit};
+///
+/// let config = "verbose".to_string();
+/// let cell = LazyRef::new();
+///
+/// let maybe = MaybeInit::from_cell(&cell);
+/// assert!(maybe.is_pending());
+/// assert_eq!(maybe.map(String::len), None);
+///
+/// cell.set(&config);
+/// assert_eq!(maybe.get(), Some(&config));
+/// assert!(maybe.refresh().is_ready());
+/// assert_eq!(maybe.map(String::len), Some(7));
+/// ```
+///
+/// The cell may be borrowed for less than the lifetime of the reference,
+/// e.g. when it is a field of `self`:
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, MaybeInit};
+///
+/// struct Registry {
+///     locale: LazyRef<'static, String>,
+/// }
+///
+/// impl Registry {
+///     fn locale(&self) -> MaybeInit<'_, 'static, String> {
+///         MaybeInit::from_cell(&self.locale)
+///     }
+/// }
+///
+/// let registry = Registry { locale: LazyRef::new() };
+/// assert!(registry.locale().is_pending());
+///
+/// let locale = registry.locale().get_or_init(|| Box::leak(Box::new("en".to_string())));
+/// assert_eq!(locale, "en");
+/// assert!(registry.locale().is_ready());
+/// ```
+pub enum MaybeInit<'c, 'a, T> {
+    /// The reference is initialized.
+    Ready(&'a T),
+    /// The reference is going to be published to the cell.
+    Pending(&'c LazyRef<'a, T>),
+}
+
+impl<T> Clone for MaybeInit<'_, '_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for MaybeInit<'_, '_, T> {}
+
+impl<T: Debug> Debug for MaybeInit<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ready(r) => f.debug_tuple("Ready").field(r).finish(),
+            Self::Pending(cell) => f.debug_tuple("Pending").field(cell).finish(),
+        }
+    }
+}
+
+impl<'a, T> From<&'a T> for MaybeInit<'_, 'a, T> {
+    #[inline]
+    fn from(r: &'a T) -> Self {
+        Self::Ready(r)
+    }
+}
+
+impl<'c, 'a, T> From<&'c LazyRef<'a, T>> for MaybeInit<'c, 'a, T> {
+    #[inline]
+    fn from(cell: &'c LazyRef<'a, T>) -> Self {
+        Self::from_cell(cell)
+    }
+}
+
+impl<'c, 'a, T> MaybeInit<'c, 'a, T> {
+    /// Creates a ready reference if `cell` is initialized, and a pending one
+    /// otherwise.
+    #[inline]
+    #[must_use]
+    pub fn from_cell(cell: &'c LazyRef<'a, T>) -> Self {
+        match cell.get() {
+            Some(r) => Self::Ready(r),
+            None => Self::Pending(cell),
+        }
+    }
+
+    /// Checks whether the reference is ready.
+    ///
+    /// A pending reference whose cell has been initialized since stays
+    /// pending until [`refresh`](Self::refresh)ed.
+    #[inline]
+    #[must_use]
+    pub const fn is_ready(&self) -> bool {
+        matches!(self, Self::Ready(_))
+    }
+
+    /// Checks whether the reference is pending.
+    #[inline]
+    #[must_use]
+    pub const fn is_pending(&self) -> bool {
+        !self.is_ready()
+    }
+
+    /// Gets the reference, checking the cell of a pending one.
+    ///
+    /// Returns `None` if the cell is still empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        match *self {
+            Self::Ready(r) => Some(r),
+            Self::Pending(cell) => cell.get(),
+        }
+    }
+
+    /// Returns the ready reference if the cell of a pending one has been
+    /// initialized, and `self` otherwise.
+    #[inline]
+    #[must_use]
+    pub fn refresh(self) -> Self {
+        match self {
+            Self::Ready(_) => self,
+            Self::Pending(cell) => Self::from_cell(cell),
+        }
+    }
+
+    /// Returns the cell of a pending reference.
+    ///
+    /// Returns `None` if the reference is ready.
+    #[inline]
+    #[must_use]
+    pub const fn cell(&self) -> Option<&'c LazyRef<'a, T>> {
+        match *self {
+            Self::Ready(_) => None,
+            Self::Pending(cell) => Some(cell),
+        }
+    }
+
+    /// Gets the reference, initializing the cell of a pending one with `f` if
+    /// it is still empty.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(self, f: impl FnOnce() -> &'a T) -> &'a T {
+        match self {
+            Self::Ready(r) => r,
+            Self::Pending(cell) => cell.get_or_init(f),
+        }
+    }
+
+    /// Gets the reference, initializing the cell of a pending one with the
+    /// value of the handle returned by `f` if it is still empty.
+    ///
+    /// Behaves like [`get_or_init`](Self::get_or_init), but accepts any
+    /// [`StableRef`], like [`LazyRef::get_or_init_from`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init_from<R: StableRef<'a, Target = T>>(self, f: impl FnOnce() -> R) -> &'a T {
+        match self {
+            Self::Ready(r) => r,
+            Self::Pending(cell) => cell.get_or_init_from(f),
+        }
+    }
+
+    /// Gets the reference, or `default` if the cell of a pending one is
+    /// still empty, leaving the cell empty.
+    #[inline]
+    #[must_use]
+    pub fn unwrap_or(self, default: &'a T) -> &'a T {
+        self.get().unwrap_or(default)
+    }
+
+    /// Maps the reference with `f`, checking the cell of a pending one.
+    ///
+    /// Returns `None` if the cell is still empty.
+    #[inline]
+    pub fn map<U>(self, f: impl FnOnce(&'a T) -> U) -> Option<U> {
+        self.get().map(f)
+    }
+}
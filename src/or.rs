@@ -0,0 +1,116 @@
+use core::{
+    fmt::{Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::LazyRef;
+
+/// A [`LazyRef`] dereferencing to its reference, or to a fallback while the
+/// cell is empty.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyRefOr;
+///
+/// static DEFAULT_TIMEOUT: u64 = 30;
+/// static TIMEOUT: LazyRefOr<'static, u64> = LazyRefOr::new(&DEFAULT_TIMEOUT);
+///
+/// assert_eq!(*TIMEOUT, 30);
+///
+/// static CONFIGURED: u64 = 5;
+/// TIMEOUT.set(&CONFIGURED);
+/// assert_eq!(*TIMEOUT, 5);
+/// ```
+pub struct LazyRefOr<'a, T> {
+    inner: LazyRef<'a, T>,
+    fallback: &'a T,
+}
+
+impl<T> Clone for LazyRefOr<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            fallback: self.fallback,
+        }
+    }
+}
+
+impl<T: Debug> Debug for LazyRefOr<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyRefOr")
+            .field("inner", &self.inner)
+            .field("fallback", &self.fallback)
+            .finish()
+    }
+}
+
+impl<T> Deref for LazyRefOr<'_, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.get_or_fallback()
+    }
+}
+
+impl<'a, T> LazyRefOr<'a, T> {
+    /// Creates a new empty cell dereferencing to `fallback` until
+    /// initialized.
+    #[inline]
+    #[must_use]
+    pub const fn new(fallback: &'a T) -> Self {
+        Self {
+            inner: LazyRef::new(),
+            fallback,
+        }
+    }
+
+    /// Returns the fallback reference.
+    #[inline]
+    #[must_use]
+    pub fn fallback(&self) -> &'a T {
+        self.fallback
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Gets the underlying reference, or the fallback if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get_or_fallback(&self) -> &'a T {
+        self.get().unwrap_or(self.fallback)
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        self.inner.get_or_init(f)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`.
+    #[inline]
+    pub fn set(&self, r: &'a T) {
+        self.inner.set(r);
+    }
+}
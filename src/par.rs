@@ -0,0 +1,60 @@
+use rayon::prelude::*;
+
+use crate::{LazyContainer, LazyRef};
+
+/// Initializes every empty cell of `cells` in parallel using `f`, which is
+/// given the index of the cell.
+///
+/// Cells that are already initialized, or get initialized concurrently by
+/// other threads, are left as is.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{par_init_all, LazyRef, LazyRefSliceExt};
+///
+/// let values: Vec<usize> = (0..1000).collect();
+/// let cells: Vec<LazyRef<'_, usize>> = values.iter().map(|_| LazyRef::new()).collect();
+///
+/// par_init_all(&cells, |idx| &values[idx]);
+/// assert!(cells.iter_initialized().eq(&values));
+/// ```
+#[inline]
+pub fn par_init_all<'a, T: Sync>(cells: &[LazyRef<'a, T>], f: impl Fn(usize) -> &'a T + Sync) {
+    cells.par_iter().enumerate().for_each(|(idx, cell)| {
+        let _ = cell.get_or_init(|| f(idx));
+    });
+}
+
+/// Initializes the empty entries of `keys` in `cells` in parallel using `f`,
+/// which is given the key of the entry.
+///
+/// Entries that are already initialized, or get initialized concurrently by
+/// other threads, are left as is, and the keys missing from `cells` are
+/// skipped.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{par_init_keys, LazyFn};
+///
+/// let arena: Vec<String> = (0..100).map(|n| n.to_string()).collect();
+/// let names = LazyFn::new(|n: &usize| &arena[*n]);
+///
+/// par_init_keys(&names, 0..50, |n| &arena[n]);
+/// assert_eq!(names.len(), 50);
+/// ```
+#[inline]
+pub fn par_init_keys<'a, K, T, C>(
+    cells: &C,
+    keys: impl IntoParallelIterator<Item = K>,
+    f: impl Fn(K) -> &'a T + Sync,
+) where
+    K: Clone + Send,
+    T: 'a,
+    C: LazyContainer<'a, K, T> + Sync + ?Sized,
+{
+    keys.into_par_iter().for_each(|key| {
+        let _ = cells.get_or_init(key.clone(), || f(key));
+    });
+}
@@ -0,0 +1,94 @@
+use std::time::{Duration, Instant};
+
+use parking_lot_core::{park, unpark_all, DEFAULT_PARK_TOKEN, DEFAULT_UNPARK_TOKEN};
+
+use crate::{Error, LazyRef};
+
+impl<'a, T> LazyRef<'a, T> {
+    /// Blocks the current thread until the cell is initialized, returning the
+    /// underlying reference.
+    ///
+    /// Waiting threads are parked in the global queue of `parking_lot`
+    /// keyed on the address of the cell, so the cell stays a single word.
+    /// In exchange, every publication to the cell looks up that queue to wake
+    /// them.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRef;
+    ///
+    /// let value = 42;
+    /// let cell = LazyRef::new();
+    ///
+    /// std::thread::scope(|s| {
+    ///     s.spawn(|| assert_eq!(cell.wait(), &42));
+    ///     cell.set(&value);
+    /// });
+    /// ```
+    #[inline]
+    pub fn wait(&self) -> &'a T {
+        loop {
+            if let Some(r) = self.get() {
+                return r;
+            }
+            self.park(None);
+        }
+    }
+
+    /// Blocks the current thread until the cell is initialized or `timeout`
+    /// elapses, returning the underlying reference.
+    ///
+    /// Waits like [`wait`](Self::wait).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if `timeout` elapses first.
+    #[inline]
+    pub fn wait_timeout(&self, timeout: Duration) -> Result<&'a T, Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(r) = self.get() {
+                return Ok(r);
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::Timeout);
+            }
+            self.park(Some(deadline));
+        }
+    }
+
+    /// Parks the current thread until the cell is initialized, woken
+    /// spuriously or `deadline` passes.
+    #[inline]
+    fn park(&self, deadline: Option<Instant>) {
+        // SAFETY:
+        // This is safe because the cell owns its address, and the callbacks neither
+        // panic nor call into `parking_lot`. Checking the cell under the queue lock
+        // ensures that a publication either is seen or wakes the thread.
+        let _ = unsafe {
+            park(
+                self.park_key(),
+                || !self.is_initialized(),
+                || {},
+                |_, _| {},
+                DEFAULT_PARK_TOKEN,
+                deadline,
+            )
+        };
+    }
+
+    /// Wakes all the threads waiting for the cell to be initialized.
+    #[inline]
+    pub(crate) fn unpark_waiters(&self) {
+        // SAFETY:
+        // This is safe because the cell owns its address.
+        let _ = unsafe { unpark_all(self.park_key(), DEFAULT_UNPARK_TOKEN) };
+    }
+
+    /// Returns the key of the queue of the threads waiting for the cell.
+    #[inline]
+    fn park_key(&self) -> usize {
+        core::ptr::from_ref(self).addr()
+    }
+}
@@ -0,0 +1,146 @@
+use core::{
+    alloc::Layout,
+    fmt::{Debug, Formatter},
+    pin::Pin,
+};
+
+use crate::LazyRef;
+
+/// A non-blocking synchronization primitive (cell) for lazy-initialized
+/// pinned immutable references.
+///
+/// The same as [`LazyRef`], but only accepts pinned references and hands out
+/// pinned references, preserving the pinning guarantee through the cell.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::pin::Pin;
+/// use lazy_ref::LazyPin;
+///
+/// let node = Box::pin(42);
+/// let lazy_pin = LazyPin::new();
+///
+/// let r: Pin<&i32> = lazy_pin.get_or_init(|| node.as_ref());
+/// assert_eq!(*r, 42);
+/// assert_eq!(lazy_pin.get(), Some(node.as_ref()));
+/// ```
+///
+/// The cell has the same layout as [`LazyRef`].
+#[repr(transparent)]
+pub struct LazyPin<'a, T> {
+    inner: LazyRef<'a, T>,
+}
+
+impl<T> Clone for LazyPin<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: PartialEq> PartialEq for LazyPin<'_, T> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.inner == other.inner
+    }
+}
+
+impl<T: Eq> Eq for LazyPin<'_, T> {}
+
+impl<T: Debug> Debug for LazyPin<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("LazyPin");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for LazyPin<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const _: () = {
+    assert!(LazyPin::<'static, u64>::LAYOUT.size() == size_of::<*mut u64>());
+    assert!(LazyPin::<'static, u64>::LAYOUT.align() == align_of::<*mut u64>());
+};
+
+impl<'a, T> LazyPin<'a, T> {
+    /// The layout of the cell, the same as of `*mut T`.
+    pub const LAYOUT: Layout = Layout::new::<Self>();
+
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+        }
+    }
+
+    /// Creates a new initialized cell.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized(r: Pin<&'a T>) -> Self {
+        Self {
+            inner: LazyRef::new_initialized(r.get_ref()),
+        }
+    }
+
+    /// Gets the underlying pinned reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<Pin<&'a T>> {
+        self.inner.get().map(Self::repin)
+    }
+
+    /// Gets the underlying pinned reference of the cell, initializing it with
+    /// `f` if the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> Pin<&'a T>) -> Pin<&'a T> {
+        Self::repin(self.inner.get_or_init(|| f().get_ref()))
+    }
+
+    /// Consumes the `LazyPin`, returning the wrapped pinned reference.
+    /// Returns `None` if the cell was empty.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<Pin<&'a T>> {
+        self.inner.into_inner().map(Self::repin)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`.
+    #[inline]
+    pub fn set(&self, r: Pin<&'a T>) {
+        self.inner.set(r.get_ref());
+    }
+
+    /// Pins a reference taken out of the inner cell.
+    #[inline]
+    fn repin(r: &'a T) -> Pin<&'a T> {
+        // SAFETY:
+        // This is safe because the inner cell can only be set from pinned references.
+        unsafe { Pin::new_unchecked(r) }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    fmt::{Debug, Formatter},
+    thread::{Scope, ScopedJoinHandle},
+};
+
+use crate::LazyRef;
+
+/// A handle publishing the results of scoped worker threads to a
+/// [`LazyRef`].
+///
+/// The workers spawned by [`spawn`](Self::spawn) can only publish references
+/// borrowed from outside of the [`std::thread::scope`], which are guaranteed
+/// to outlive the scope and the cell.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::thread;
+///
+/// use lazy_ref::{LazyRef, ScopePublisher};
+///
+/// let shards = [vec![3, 1], vec![4, 1, 5], vec![9, 2]];
+/// let largest = LazyRef::new();
+///
+/// thread::scope(|s| {
+///     let publisher = ScopePublisher::new(&largest);
+///     for shard in &shards {
+///         let _ = publisher.spawn(s, || shard.iter().max().unwrap());
+///     }
+/// });
+/// assert!(largest.is_initialized());
+/// ```
+///
+/// References to the values of the scope itself are rejected:
+///
+/// ```compile_fail
+/// use std::thread;
+///
+/// use lazy_ref::{LazyRef, ScopePublisher};
+///
+/// let cell = LazyRef::new();
+///
+/// thread::scope(|s| {
+///     let local = 42;
+///     let _ = ScopePublisher::new(&cell).spawn(s, || &local);
+/// });
+/// ```
+pub struct ScopePublisher<'c, 'env, T> {
+    cell: &'c LazyRef<'env, T>,
+}
+
+impl<T> Clone for ScopePublisher<'_, '_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ScopePublisher<'_, '_, T> {}
+
+impl<T: Debug> Debug for ScopePublisher<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ScopePublisher").field(self.cell).finish()
+    }
+}
+
+impl<'c, 'env, T: Sync> ScopePublisher<'c, 'env, T> {
+    /// Creates a new publisher to `cell`.
+    #[inline]
+    #[must_use]
+    pub const fn new(cell: &'c LazyRef<'env, T>) -> Self {
+        Self { cell }
+    }
+
+    /// Returns the cell the references are published to.
+    #[inline]
+    #[must_use]
+    pub fn cell(&self) -> &'c LazyRef<'env, T> {
+        self.cell
+    }
+
+    /// Spawns a worker thread in `scope` initializing the cell with `f`,
+    /// returning the handle to join it.
+    ///
+    /// The worker behaves like [`LazyRef::get_or_init`] and returns the
+    /// published reference when joined.
+    #[inline]
+    pub fn spawn<'scope>(
+        &self,
+        scope: &'scope Scope<'scope, 'env>,
+        f: impl FnOnce() -> &'env T + Send + 'scope,
+    ) -> ScopedJoinHandle<'scope, &'env T>
+    where
+        'c: 'scope,
+    {
+        let cell = self.cell;
+        scope.spawn(move || cell.get_or_init(f))
+    }
+}
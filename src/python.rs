@@ -0,0 +1,136 @@
+use std::{
+    boxed::Box,
+    fmt::{Debug, Formatter},
+};
+
+use pyo3::{Py, Python};
+
+use crate::LazyRef;
+
+/// A cell owning a lazily computed Python object, for the objects an
+/// extension module computes once per process.
+///
+/// The object is computed while attached to the interpreter, which is proven
+/// by the [`Python`] token. If the initializer detaches from the interpreter,
+/// for example by calling into Python code, other threads may compute the
+/// object concurrently, in which case the first published object wins and
+/// the rest are released. Reading the object takes neither the token nor a
+/// lock.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyPyRef;
+/// use pyo3::{types::PyAnyMethods, IntoPyObject, Python};
+///
+/// static ANSWER: LazyPyRef<pyo3::types::PyInt> = LazyPyRef::new();
+///
+/// Python::initialize();
+/// Python::attach(|py| {
+///     let answer = ANSWER.get_or_init(py, |py| 42_i32.into_pyobject(py).unwrap().unbind());
+///     assert_eq!(answer.bind(py).extract::<i32>().unwrap(), 42);
+///     assert!(ANSWER.get().is_some());
+/// });
+/// ```
+pub struct LazyPyRef<T: 'static> {
+    /// The cell owning the boxed object.
+    inner: LazyRef<'static, Py<T>>,
+}
+
+impl<T: 'static> Debug for LazyPyRef<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_tuple("LazyPyRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T: 'static> Default for LazyPyRef<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: 'static> Drop for LazyPyRef<T> {
+    #[inline]
+    fn drop(&mut self) {
+        if let Some(r) = self.inner.take_owned() {
+            // SAFETY:
+            // This is safe because the cell only holds references leaked from boxes by
+            // `publish`, and it was the only owner of this one.
+            drop(unsafe { Box::from_raw(core::ptr::from_ref(r).cast_mut()) });
+        }
+    }
+}
+
+impl<T: 'static> LazyPyRef<T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+        }
+    }
+
+    /// Gets the object.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&Py<T>> {
+        self.inner.get()
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Gets the object, computing it with `f` if the cell is empty.
+    #[inline]
+    pub fn get_or_init(&self, py: Python<'_>, f: impl FnOnce(Python<'_>) -> Py<T>) -> &Py<T> {
+        match self.get() {
+            Some(object) => object,
+            None => self.publish(f(py)),
+        }
+    }
+
+    /// Gets the object, computing it with `f` if the cell is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `f`, leaving the cell empty.
+    #[inline]
+    pub fn get_or_try_init<E>(
+        &self,
+        py: Python<'_>,
+        f: impl FnOnce(Python<'_>) -> Result<Py<T>, E>,
+    ) -> Result<&Py<T>, E> {
+        match self.get() {
+            Some(object) => Ok(object),
+            None => Ok(self.publish(f(py)?)),
+        }
+    }
+
+    /// Publishes `object` unless the cell is initialized, returning the
+    /// published object and releasing `object` if it lost the race.
+    fn publish(&self, object: Py<T>) -> &Py<T> {
+        let r: &'static Py<T> = Box::leak(Box::new(object));
+        let published = self.inner.publish_if_empty(r);
+        if !core::ptr::eq(published, r) {
+            // SAFETY:
+            // This is safe because `r` has just been leaked from a box and has not been
+            // published.
+            drop(unsafe { Box::from_raw(core::ptr::from_ref(r).cast_mut()) });
+        }
+        published
+    }
+}
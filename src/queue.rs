@@ -0,0 +1,254 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{fence, AtomicUsize, Ordering},
+};
+
+use crate::{Backoff, LazyRef, SpinBackoff};
+
+/// A slot of a [`LazyRefQueue`].
+struct Slot<'a, T> {
+    /// The position the slot is next pushed at, plus one once the reference
+    /// is written.
+    stamp: AtomicUsize,
+    cell: LazyRef<'a, T>,
+}
+
+/// A bounded lock-free FIFO queue of `N` immutable references, for any number
+/// of producers and consumers.
+///
+/// Moving references through the queue never allocates. The positions of the
+/// queue are packed with the number of laps around the ring, so that every
+/// slot knows whether it is due to be pushed to or popped from.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyRefQueue;
+///
+/// let batches: Vec<Vec<u32>> = (0..4).map(|n| vec![n; 3]).collect();
+/// let queue = LazyRefQueue::<'_, Vec<u32>, 2>::new();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         for batch in &batches {
+///             while queue.push(batch).is_err() {
+///                 std::thread::yield_now();
+///             }
+///         }
+///     });
+///     let mut received = 0;
+///     while received < batches.len() {
+///         if let Some(batch) = queue.pop() {
+///             assert_eq!(batch, &batches[received]);
+///             received += 1;
+///         }
+///     }
+/// });
+/// assert!(queue.is_empty());
+/// ```
+pub struct LazyRefQueue<'a, T, const N: usize> {
+    /// The position to pop from next.
+    head: AtomicUsize,
+    /// The position to push to next.
+    tail: AtomicUsize,
+    slots: [Slot<'a, T>; N],
+}
+
+impl<T, const N: usize> Debug for LazyRefQueue<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LazyRefQueue")
+            .field("len", &self.len())
+            .field("capacity", &N)
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for LazyRefQueue<'_, T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> LazyRefQueue<'a, T, N> {
+    /// The distance between the positions of the same slot in adjacent laps,
+    /// the lowest power of two greater than `N`.
+    const ONE_LAP: usize = (N + 1).next_power_of_two();
+
+    /// Creates a new empty queue.
+    ///
+    /// Fails to compile if `N` is zero.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "`LazyRefQueue` must have at least one slot") };
+        let mut slots = [const {
+            Slot {
+                stamp: AtomicUsize::new(0),
+                cell: LazyRef::new(),
+            }
+        }; N];
+        let mut idx = 0;
+        while idx < N {
+            slots[idx].stamp = AtomicUsize::new(idx);
+            idx += 1;
+        }
+        Self {
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            slots,
+        }
+    }
+
+    /// Returns the number of the references the queue can hold.
+    #[inline]
+    #[must_use]
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Pushes `r` to the back of the queue.
+    ///
+    /// # Errors
+    ///
+    /// Returns `r` back if the queue is full.
+    #[inline]
+    pub fn push(&self, r: &'a T) -> Result<(), &'a T> {
+        let mut backoff = SpinBackoff::default();
+        let mut tail = self.tail.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[tail & (Self::ONE_LAP - 1)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            if stamp == tail {
+                match self.tail.compare_exchange_weak(
+                    tail,
+                    Self::next(tail),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        slot.cell
+                            .ptr
+                            .store(core::ptr::from_ref(r).cast_mut(), Ordering::Relaxed);
+                        slot.stamp.store(tail + 1, Ordering::Release);
+                        return Ok(());
+                    }
+                    Err(actual) => tail = actual,
+                }
+            } else if stamp.wrapping_add(Self::ONE_LAP) == tail + 1 {
+                // The slot still holds the reference of the previous lap.
+                fence(Ordering::SeqCst);
+                if self
+                    .head
+                    .load(Ordering::Relaxed)
+                    .wrapping_add(Self::ONE_LAP)
+                    == tail
+                {
+                    return Err(r);
+                }
+                backoff.snooze();
+                tail = self.tail.load(Ordering::Relaxed);
+            } else {
+                backoff.snooze();
+                tail = self.tail.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Pops the reference at the front of the queue.
+    ///
+    /// Returns `None` if the queue is empty.
+    #[inline]
+    #[must_use]
+    pub fn pop(&self) -> Option<&'a T> {
+        let mut backoff = SpinBackoff::default();
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            let slot = &self.slots[head & (Self::ONE_LAP - 1)];
+            let stamp = slot.stamp.load(Ordering::Acquire);
+            if stamp == head + 1 {
+                match self.head.compare_exchange_weak(
+                    head,
+                    Self::next(head),
+                    Ordering::SeqCst,
+                    Ordering::Relaxed,
+                ) {
+                    Ok(_) => {
+                        let ptr = slot.cell.ptr.load(Ordering::Relaxed);
+                        slot.stamp
+                            .store(head.wrapping_add(Self::ONE_LAP), Ordering::Release);
+                        // SAFETY:
+                        // This is safe because the pointer has been created from a valid
+                        // reference by `push`, which the acquire load of the stamp has
+                        // synchronized with.
+                        return Some(unsafe { &*ptr });
+                    }
+                    Err(actual) => head = actual,
+                }
+            } else if stamp == head {
+                // The slot hasn't been pushed to in this lap yet.
+                fence(Ordering::SeqCst);
+                if self.tail.load(Ordering::Relaxed) == head {
+                    return None;
+                }
+                backoff.snooze();
+                head = self.head.load(Ordering::Relaxed);
+            } else {
+                backoff.snooze();
+                head = self.head.load(Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Returns the number of the references in the queue.
+    ///
+    /// The result may be outdated as soon as it is returned if the queue is
+    /// accessed concurrently.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        loop {
+            let tail = self.tail.load(Ordering::SeqCst);
+            let head = self.head.load(Ordering::SeqCst);
+            if self.tail.load(Ordering::SeqCst) == tail {
+                let head_idx = head & (Self::ONE_LAP - 1);
+                let tail_idx = tail & (Self::ONE_LAP - 1);
+                return if head_idx < tail_idx {
+                    tail_idx - head_idx
+                } else if head_idx > tail_idx {
+                    N - head_idx + tail_idx
+                } else if head == tail {
+                    0
+                } else {
+                    N
+                };
+            }
+        }
+    }
+
+    /// Checks whether the queue holds no references.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks whether the queue holds `N` references.
+    #[inline]
+    #[must_use]
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    /// Returns the position following `pos`, wrapping around to the next lap
+    /// after the last slot.
+    #[inline]
+    const fn next(pos: usize) -> usize {
+        if (pos & (Self::ONE_LAP - 1)) + 1 < N {
+            pos + 1
+        } else {
+            (pos & !(Self::ONE_LAP - 1)).wrapping_add(Self::ONE_LAP)
+        }
+    }
+}
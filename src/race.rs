@@ -0,0 +1,84 @@
+use std::{
+    sync::{PoisonError, RwLock},
+    thread::Thread,
+};
+
+/// The hook reporting the lost initialization races.
+static HOOK: RwLock<Option<fn(&InitRace)>> = RwLock::new(None);
+
+/// A lost initialization race, reported to the hook set with
+/// [`set_init_race_hook`].
+#[derive(Debug)]
+pub struct InitRace {
+    cell: usize,
+    thread: Thread,
+}
+
+impl InitRace {
+    /// Returns the address of the cell.
+    #[inline]
+    #[must_use]
+    pub fn cell_addr(&self) -> usize {
+        self.cell
+    }
+
+    /// Returns the thread whose initializer lost the race.
+    #[inline]
+    #[must_use]
+    pub fn thread(&self) -> &Thread {
+        &self.thread
+    }
+}
+
+/// Sets the hook called when the initializer of
+/// [`LazyRef::get_or_init`](crate::LazyRef::get_or_init) loses the race
+/// to publish its reference, returning the previous hook.
+///
+/// The hook is called on the losing thread, after its reference has been
+/// discarded, and only in debug builds, so that the unexpected duplicate
+/// initializations can be found during development. Passing `None` removes
+/// the hook.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+///
+/// use lazy_ref::{set_init_race_hook, InitRace, LazyRef};
+///
+/// static RACES: AtomicUsize = AtomicUsize::new(0);
+///
+/// fn count(race: &InitRace) {
+///     assert_eq!(race.thread().id(), std::thread::current().id());
+///     RACES.fetch_add(1, Ordering::Relaxed);
+/// }
+///
+/// let (mine, theirs) = (1, 2);
+/// let cell = LazyRef::new();
+///
+/// set_init_race_hook(Some(count));
+/// // Another publisher gets ahead of the initializer.
+/// assert_eq!(cell.get_or_init(|| { cell.set(&theirs); &mine }), &2);
+/// set_init_race_hook(None);
+///
+/// // The race is only reported in debug builds of this crate.
+/// assert!(RACES.load(Ordering::Relaxed) <= 1);
+/// ```
+#[inline]
+pub fn set_init_race_hook(hook: Option<fn(&InitRace)>) -> Option<fn(&InitRace)> {
+    let mut current = HOOK.write().unwrap_or_else(PoisonError::into_inner);
+    core::mem::replace(&mut *current, hook)
+}
+
+/// Reports a lost initialization race of the cell at `addr` to the hook.
+#[cfg(debug_assertions)]
+#[cold]
+pub(crate) fn report(addr: usize) {
+    let hook = *HOOK.read().unwrap_or_else(PoisonError::into_inner);
+    if let Some(hook) = hook {
+        hook(&InitRace {
+            cell: addr,
+            thread: std::thread::current(),
+        });
+    }
+}
@@ -0,0 +1,153 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+};
+
+use crate::{Backoff, LazyRef, SpinBackoff};
+
+/// A [`LazyRef`] whose racing initializers are ranked, so that the reference
+/// of the highest rank wins.
+///
+/// Unlike [`LazyRef::get_or_init`], a reference is replaced by any later one
+/// of a higher rank, while the references of lower or equal ranks are
+/// discarded. This allows to race a fast approximate initializer against a
+/// slow exact one, making the exact result stick whichever finishes first.
+///
+/// Publications briefly spin while another one is in progress. Reads never
+/// spin.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::RankedLazyRef;
+///
+/// let (approximate, exact) = (3.0, 3.14159);
+/// let pi = RankedLazyRef::new();
+///
+/// assert_eq!(pi.get_or_init_preferring(0, || &approximate), &3.0);
+/// assert_eq!(pi.get_or_init_preferring(1, || &exact), &3.14159);
+/// assert_eq!(pi.get_or_init_preferring(0, || &approximate), &3.14159);
+/// assert_eq!(pi.rank(), Some(1));
+/// ```
+pub struct RankedLazyRef<'a, T> {
+    inner: LazyRef<'a, T>,
+    /// The rank of the reference, meaningless while the cell is empty.
+    ///
+    /// Ranks only increase and are stored after the references, so a stale
+    /// rank is never higher than the actual one.
+    rank: AtomicU32,
+    /// Whether a publication is in progress.
+    locked: AtomicBool,
+}
+
+impl<T: Debug> Debug for RankedLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("RankedLazyRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for RankedLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> RankedLazyRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+            rank: AtomicU32::new(0),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// Creates a new cell initialized with `r` of `rank`.
+    #[inline]
+    #[must_use]
+    pub const fn new_initialized(rank: u32, r: &'a T) -> Self {
+        Self {
+            inner: LazyRef::new_initialized(r),
+            rank: AtomicU32::new(rank),
+            locked: AtomicBool::new(false),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Returns the rank of the underlying reference.
+    ///
+    /// While a reference is being replaced, the rank of the previous one may
+    /// be returned. Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn rank(&self) -> Option<u32> {
+        let rank = self.rank.load(Ordering::Acquire);
+        self.is_initialized().then_some(rank)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty or its reference has a lower rank than `rank`.
+    ///
+    /// If, while `f` runs, another thread publishes a reference of a rank not
+    /// lower than `rank`, the result of `f` is discarded and that reference
+    /// is returned instead.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init_preferring(&self, rank: u32, f: impl FnOnce() -> &'a T) -> &'a T {
+        if let Some(r) = self.get_ranked_at_least(rank) {
+            return r;
+        }
+        let r = f();
+        self.lock();
+        let current = self.get_ranked_at_least(rank);
+        if current.is_none() {
+            self.inner.force_set(r);
+            self.rank.store(rank, Ordering::Release);
+        }
+        self.locked.store(false, Ordering::Release);
+        current.unwrap_or(r)
+    }
+
+    /// Gets the underlying reference if its rank is not lower than `rank`.
+    #[inline]
+    fn get_ranked_at_least(&self, rank: u32) -> Option<&'a T> {
+        let current = self.rank()?;
+        (current >= rank).then(|| self.get()).flatten()
+    }
+
+    /// Waits for other publications and marks one as in progress.
+    fn lock(&self) {
+        let mut backoff = SpinBackoff::default();
+        while self
+            .locked
+            .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            backoff.snooze();
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use std::{cell::RefCell, thread_local, vec::Vec};
+
+thread_local! {
+    /// Addresses of the cells being initialized by the current thread.
+    static IN_PROGRESS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a cell as being initialized by the current thread until dropped.
+pub(crate) struct InitGuard {
+    addr: usize,
+}
+
+impl InitGuard {
+    /// Marks the cell at `addr` as being initialized.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the cell is already being initialized by the current thread.
+    #[inline]
+    pub(crate) fn enter(addr: usize) -> Self {
+        IN_PROGRESS.with_borrow_mut(|cells| {
+            assert!(
+                !cells.contains(&addr),
+                "reentrant initialization: the initializer of a lazy cell tried to initialize the \
+                 same cell"
+            );
+            cells.push(addr);
+        });
+        Self { addr }
+    }
+}
+
+impl Drop for InitGuard {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = IN_PROGRESS.try_with(|cells| {
+            let mut cells = cells.borrow_mut();
+            if let Some(idx) = cells.iter().rposition(|&addr| addr == self.addr) {
+                cells.swap_remove(idx);
+            }
+        });
+    }
+}
@@ -0,0 +1,205 @@
+use core::{
+    fmt::{Debug, Formatter},
+    marker::{PhantomData, PhantomPinned},
+    pin::Pin,
+    sync::atomic::{AtomicIsize, Ordering},
+};
+
+use crate::VarianceMarker;
+
+/// A non-blocking synchronization primitive (cell) for lazy-initialized
+/// immutable references, storing the offset of the referenced value from the
+/// cell itself.
+///
+/// As long as the cell and the referenced value are moved together, e.g. as
+/// parts of a memory-mapped file reloaded at another address, the cell keeps
+/// referencing the value. Since moving only the cell would break the
+/// reference, the cell is only accessible through [`Pin`].
+///
+/// A zeroed `RelativeLazyRef` is an empty cell. The cell cannot reference its
+/// own address.
+///
+/// The address of the referenced value is reconstructed from an integer,
+/// so memory the references point into must have its provenance exposed, e.g.
+/// by [`expose_provenance`]. This is done automatically for the references
+/// the cell is set with in the current process.
+///
+/// [`expose_provenance`]: https://doc.rust-lang.org/std/primitive.pointer.html#method.expose_provenance
+///
+/// # Examples
+///
+/// ```rust
+/// use std::pin::pin;
+/// use lazy_ref::RelativeLazyRef;
+///
+/// let value = 42;
+/// let cell = pin!(RelativeLazyRef::new());
+///
+/// assert_eq!(cell.as_ref().get_or_init(|| &value), &42);
+/// assert!(std::ptr::eq(cell.as_ref().get().unwrap(), &value));
+/// ```
+///
+/// # Reloading at another address
+///
+/// A cell found in memory reloaded at another address, such as a remapped
+/// file, can only be reached through [`Pin::new_unchecked`], whose caller
+/// must guarantee that:
+///
+/// - the memory holds the bytes of a cell together with the value it
+///   references, at the same offset from each other as when the cell was set;
+/// - neither of them is moved or freed, and the value isn't mutated, for as
+///   long as `'a`;
+/// - the provenance of the memory is exposed.
+///
+/// ```rust
+/// use std::{mem::MaybeUninit, pin::Pin};
+///
+/// use lazy_ref::RelativeLazyRef;
+///
+/// #[repr(C)]
+/// struct Region {
+///     cell: RelativeLazyRef<'static, u64>,
+///     value: u64,
+/// }
+///
+/// let old: &'static Region = Box::leak(Box::new(Region {
+///     cell: RelativeLazyRef::new(),
+///     value: 42,
+/// }));
+/// // SAFETY: the leaked region is never moved.
+/// let cell = unsafe { Pin::new_unchecked(&old.cell) };
+/// cell.set(&old.value);
+///
+/// // Reload the region at another address, as a remapped file would be.
+/// let new: &'static mut MaybeUninit<Region> = Box::leak(Box::new(MaybeUninit::uninit()));
+/// // SAFETY: the regions are both valid for a `Region` and don't overlap.
+/// unsafe { std::ptr::copy_nonoverlapping(old, new.as_mut_ptr(), 1) };
+/// let new = new.as_ptr();
+/// let _ = new.expose_provenance();
+///
+/// // SAFETY: the copy holds the cell together with its value, is never moved,
+/// // freed or mutated, and has its provenance exposed.
+/// let cell = unsafe { Pin::new_unchecked(&(*new).cell) };
+/// assert_eq!(cell.get(), Some(&42));
+/// assert!(std::ptr::eq(cell.get().unwrap(), unsafe { &(*new).value }));
+/// ```
+pub struct RelativeLazyRef<'a, T> {
+    /// The offset of the value from the cell, or zero if the cell is empty.
+    offset: AtomicIsize,
+    _phantom: PhantomData<VarianceMarker<'a, T>>,
+    _pinned: PhantomPinned,
+}
+
+impl<T> Debug for RelativeLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("RelativeLazyRef");
+        match self.offset.load(Ordering::Relaxed) {
+            0 => d.field(&format_args!("<uninit>")),
+            offset => d.field(&offset),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for RelativeLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> RelativeLazyRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            offset: AtomicIsize::new(0),
+            _phantom: PhantomData,
+            _pinned: PhantomPinned,
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(self: Pin<&Self>) -> Option<&'a T> {
+        self.resolve(self.offset.load(Ordering::Acquire))
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Many threads may call `get_or_init` concurrently with different
+    /// initializing functions. In this case multiple functions can be
+    /// executed, but only the first published reference is kept and
+    /// returned to every caller.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference returned by `f` points to the cell itself.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(self: Pin<&Self>, f: impl FnOnce() -> &'a T) -> &'a T {
+        if let Some(r) = self.get() {
+            return r;
+        }
+        let r = f();
+        match self.offset.compare_exchange(
+            0,
+            self.offset_to(r),
+            Ordering::Release,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => r,
+            Err(offset) => self
+                .resolve(offset)
+                .expect("the exchange failed, so the cell is not empty"),
+        }
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.offset.load(Ordering::Relaxed) != 0
+    }
+
+    /// Sets the contents of this cell to `r`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r` points to the cell itself.
+    #[inline]
+    pub fn set(self: Pin<&Self>, r: &'a T) {
+        self.offset.store(self.offset_to(r), Ordering::Release);
+    }
+
+    /// Computes the offset of `r` from the cell, exposing its provenance.
+    #[inline]
+    fn offset_to(&self, r: &'a T) -> isize {
+        let offset = core::ptr::from_ref(r)
+            .expose_provenance()
+            .wrapping_sub(core::ptr::from_ref(self).addr())
+            .cast_signed();
+        assert_ne!(offset, 0, "a `RelativeLazyRef` cannot reference itself");
+        offset
+    }
+
+    /// Converts `offset` from the cell to a reference.
+    #[inline]
+    fn resolve(&self, offset: isize) -> Option<&'a T> {
+        if offset == 0 {
+            return None;
+        }
+        let addr = core::ptr::from_ref(self).addr().wrapping_add_signed(offset);
+        // SAFETY:
+        // This is safe because the offset can only be computed from a valid reference
+        // whose provenance has been exposed, and the cell is pinned, so it has
+        // not been moved apart from the referenced value.
+        Some(unsafe { &*core::ptr::with_exposed_provenance::<T>(addr) })
+    }
+}
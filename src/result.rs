@@ -0,0 +1,296 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::{
+        atomic::{AtomicU32, AtomicU64, Ordering},
+        OnceLock,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::tagged::{LazyTagged, Tagged};
+
+/// A non-blocking synchronization primitive (cell) for lazy-initialized
+/// immutable references, remembering failed initializations.
+///
+/// Holds either a successful `&'a T` or an error `&'a E`, so an expensive
+/// failing initializer is not re-run on every access. Whether a failed
+/// initialization is retried is controlled by the [`RetryPolicy`] of the cell.
+/// Both `T` and `E` must be aligned to at least two bytes, which is checked at
+/// compile time.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyResultRef;
+///
+/// let error = "connection refused".to_string();
+/// let config = LazyResultRef::<u64, String>::new();
+///
+/// assert_eq!(config.get_or_try_init(|| Err(&error)), Err(&error));
+/// assert_eq!(config.get_or_try_init(|| unreachable!()), Err(&error));
+/// assert_eq!(config.get(), Some(Err(&error)));
+/// ```
+///
+/// Retrying a failed initialization:
+///
+/// ```rust
+/// use lazy_ref::{LazyResultRef, RetryPolicy};
+///
+/// let (value, error) = (42, "timeout".to_string());
+/// let config = LazyResultRef::with_retry_policy(RetryPolicy::Always);
+///
+/// assert_eq!(config.get_or_try_init(|| Err(&error)), Err(&error));
+/// assert_eq!(config.get_or_try_init(|| Ok(&value)), Ok(&value));
+/// assert_eq!(config.get_or_try_init(|| unreachable!()), Ok(&value));
+/// assert_eq!(config.failures(), 1);
+/// ```
+pub struct LazyResultRef<'a, T, E> {
+    inner: LazyTagged<'a, T, E>,
+    policy: RetryPolicy,
+    /// The number of failed initializations.
+    failures: AtomicU32,
+    /// The time the next retry is allowed at, in nanoseconds as returned by
+    /// [`now`].
+    retry_at: AtomicU64,
+}
+
+/// Controls whether a [`LazyResultRef`] retries a failed initialization.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum RetryPolicy {
+    /// Failures are cached forever.
+    #[default]
+    Never,
+    /// Every [`get_or_try_init`](LazyResultRef::get_or_try_init) call after a
+    /// failure retries the initialization.
+    Always,
+    /// Failures are cached for a period of time, which starts at `initial`
+    /// and doubles after every failure up to `max`.
+    Backoff {
+        /// The period after the first failure.
+        initial: Duration,
+        /// The maximum period.
+        max: Duration,
+    },
+}
+
+impl<T, E> Clone for LazyResultRef<'_, T, E> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            inner: self
+                .inner
+                .get()
+                .map_or_else(LazyTagged::new, LazyTagged::new_initialized),
+            policy: self.policy,
+            failures: AtomicU32::new(self.failures.load(Ordering::Relaxed)),
+            retry_at: AtomicU64::new(self.retry_at.load(Ordering::Relaxed)),
+        }
+    }
+}
+
+impl<T: PartialEq, E: PartialEq> PartialEq for LazyResultRef<'_, T, E> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.get() == other.get()
+    }
+}
+
+impl<T: Eq, E: Eq> Eq for LazyResultRef<'_, T, E> {}
+
+impl<T: Debug, E: Debug> Debug for LazyResultRef<'_, T, E> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_tuple("LazyResultRef");
+        match self.get() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T, E> Default for LazyResultRef<'_, T, E> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, E> From<Result<&'a T, &'a E>> for LazyResultRef<'a, T, E> {
+    #[inline]
+    fn from(r: Result<&'a T, &'a E>) -> Self {
+        Self::new_initialized(r)
+    }
+}
+
+impl<'a, T, E> From<Tagged<'a, T, E>> for Result<&'a T, &'a E> {
+    #[inline]
+    fn from(r: Tagged<'a, T, E>) -> Self {
+        match r {
+            Tagged::Left(ok) => Ok(ok),
+            Tagged::Right(err) => Err(err),
+        }
+    }
+}
+
+impl<'a, T, E> From<Result<&'a T, &'a E>> for Tagged<'a, T, E> {
+    #[inline]
+    fn from(r: Result<&'a T, &'a E>) -> Self {
+        match r {
+            Ok(ok) => Tagged::Left(ok),
+            Err(err) => Tagged::Right(err),
+        }
+    }
+}
+
+impl<'a, T, E> LazyResultRef<'a, T, E> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_retry_policy(RetryPolicy::Never)
+    }
+
+    /// Creates a new empty cell retrying failed initializations according to
+    /// `policy`.
+    #[inline]
+    #[must_use]
+    pub const fn with_retry_policy(policy: RetryPolicy) -> Self {
+        Self {
+            inner: LazyTagged::new(),
+            policy,
+            failures: AtomicU32::new(0),
+            retry_at: AtomicU64::new(0),
+        }
+    }
+
+    /// Creates a new initialized cell, which never retries failed
+    /// initializations.
+    #[inline]
+    #[must_use]
+    pub fn new_initialized(r: Result<&'a T, &'a E>) -> Self {
+        Self {
+            inner: LazyTagged::new_initialized(r.into()),
+            ..Self::new()
+        }
+    }
+
+    /// Returns the retry policy of the cell.
+    #[inline]
+    #[must_use]
+    pub fn retry_policy(&self) -> RetryPolicy {
+        self.policy
+    }
+
+    /// Returns the number of failed initializations of the cell.
+    #[inline]
+    #[must_use]
+    pub fn failures(&self) -> u32 {
+        self.failures.load(Ordering::Relaxed)
+    }
+
+    /// Gets the underlying result.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<Result<&'a T, &'a E>> {
+        self.inner.get().map(Into::into)
+    }
+
+    /// Gets the underlying result of the cell, initializing it with `f` if
+    /// the cell was empty, or if it holds an error and the
+    /// [`RetryPolicy`] allows retrying.
+    ///
+    /// Many threads may call `get_or_try_init` concurrently with different
+    /// initializing functions. In this case multiple functions can be
+    /// executed, but only the first result published in place of the
+    /// observed one is kept and returned to every caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns the cached error, or the error returned by `f`.
+    #[inline]
+    pub fn get_or_try_init(
+        &self,
+        f: impl FnOnce() -> Result<&'a T, &'a E>,
+    ) -> Result<&'a T, &'a E> {
+        let current = self.inner.get();
+        match current {
+            Some(Tagged::Left(ok)) => Ok(ok),
+            Some(Tagged::Right(err)) if !self.may_retry() => Err(err),
+            _ => self.try_initialize(current, f),
+        }
+    }
+
+    /// The slow path of [`get_or_try_init`](Self::get_or_try_init).
+    #[cold]
+    #[inline(never)]
+    fn try_initialize(
+        &self,
+        current: Option<Tagged<'a, T, E>>,
+        f: impl FnOnce() -> Result<&'a T, &'a E>,
+    ) -> Result<&'a T, &'a E> {
+        let r = f();
+        if r.is_err() {
+            self.record_failure();
+        }
+        match self.inner.compare_exchange(current, r.into()) {
+            Ok(()) => r,
+            Err(actual) => actual
+                .expect("cells are never emptied through shared access")
+                .into(),
+        }
+    }
+
+    /// Checks whether the retry policy allows retrying a failed
+    /// initialization now.
+    #[inline]
+    fn may_retry(&self) -> bool {
+        match self.policy {
+            RetryPolicy::Never => false,
+            RetryPolicy::Always => true,
+            RetryPolicy::Backoff { .. } => now() >= self.retry_at.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Counts a failed initialization and schedules the next retry.
+    fn record_failure(&self) {
+        let failures = self.failures.fetch_add(1, Ordering::Relaxed);
+        if let RetryPolicy::Backoff { initial, max } = self.policy {
+            let delay = initial
+                .checked_mul(1 << failures.min(31))
+                .map_or(max, |delay| delay.min(max));
+            let delay = u64::try_from(delay.as_nanos()).unwrap_or(u64::MAX);
+            self.retry_at
+                .store(now().saturating_add(delay), Ordering::Relaxed);
+        }
+    }
+
+    /// Consumes the `LazyResultRef`, returning the wrapped result.
+    /// Returns `None` if the cell was empty.
+    #[inline]
+    #[must_use]
+    pub fn into_inner(self) -> Option<Result<&'a T, &'a E>> {
+        self.inner.into_inner().map(Into::into)
+    }
+
+    /// Checks whether the cell is initialized, either successfully or not.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`.
+    #[inline]
+    pub fn set(&self, r: Result<&'a T, &'a E>) {
+        self.inner.set(r.into());
+    }
+}
+
+/// Returns the number of nanoseconds elapsed since the first call.
+fn now() -> u64 {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    u64::try_from(EPOCH.get_or_init(Instant::now).elapsed().as_nanos()).unwrap_or(u64::MAX)
+}
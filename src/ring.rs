@@ -0,0 +1,154 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use crate::{Backoff, LazyRef, SpinBackoff};
+
+/// A ring of the last `N` references published to it, the oldest of which is
+/// overwritten by every publication once the ring is full.
+///
+/// The ring is kept consistent with a sequence lock: publishers briefly spin
+/// while another one publishes, and so do the readers. Reading never
+/// allocates.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::RefRing;
+///
+/// let versions: Vec<String> = (1..=5).map(|n| format!("v{n}")).collect();
+/// let history = RefRing::<'_, String, 3>::new();
+/// assert_eq!(history.latest(), None);
+///
+/// for version in &versions {
+///     history.publish(version);
+/// }
+/// assert_eq!(history.latest().unwrap(), "v5");
+/// assert_eq!(history.nth_back(2).unwrap(), "v3");
+/// assert_eq!(history.nth_back(3), None);
+/// assert_eq!(history.len(), 3);
+/// ```
+pub struct RefRing<'a, T, const N: usize> {
+    slots: [LazyRef<'a, T>; N],
+    /// Twice the number of publications, plus one while a publication is in
+    /// progress.
+    seq: AtomicUsize,
+}
+
+impl<T: Debug, const N: usize> Debug for RefRing<'_, T, N> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list()
+            .entries((0..N).map_while(|k| self.nth_back(k)))
+            .finish()
+    }
+}
+
+impl<T, const N: usize> Default for RefRing<'_, T, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const N: usize> RefRing<'a, T, N> {
+    /// Creates a new empty ring.
+    ///
+    /// Fails to compile if `N` is zero.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        const { assert!(N > 0, "`RefRing` must have at least one slot") };
+        Self {
+            slots: LazyRef::new_uninit_array(),
+            seq: AtomicUsize::new(0),
+        }
+    }
+
+    /// Publishes `r` as the latest reference, overwriting the oldest one if
+    /// the ring is full.
+    #[inline]
+    pub fn publish(&self, r: &'a T) {
+        let seq = self.lock();
+        self.slots[(seq / 2) % N].force_set(r);
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Gets the latest published reference.
+    ///
+    /// Returns `None` if nothing has been published yet.
+    #[inline]
+    #[must_use]
+    pub fn latest(&self) -> Option<&'a T> {
+        self.nth_back(0)
+    }
+
+    /// Gets the `k`-th reference published before the latest one.
+    ///
+    /// Returns `None` if fewer than `k + 1` references are retained.
+    #[inline]
+    #[must_use]
+    pub fn nth_back(&self, k: usize) -> Option<&'a T> {
+        let mut backoff = SpinBackoff::default();
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                backoff.snooze();
+                continue;
+            }
+            let published = seq / 2;
+            if k >= published.min(N) {
+                return None;
+            }
+            // The acquire load keeps the following load of the sequence from being
+            // reordered before it.
+            let r = self.slots[(published - 1 - k) % N]
+                .ptr
+                .load(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                // SAFETY:
+                // This is safe because this pointer can only be created from a valid
+                // reference, or it is null.
+                return unsafe { r.as_ref() };
+            }
+        }
+    }
+
+    /// Returns the number of the retained references.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        (self.seq.load(Ordering::Relaxed) / 2).min(N)
+    }
+
+    /// Checks whether nothing has been published yet.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits for other publishers and marks a publication as in progress,
+    /// returning the sequence before it.
+    fn lock(&self) -> usize {
+        let mut backoff = SpinBackoff::default();
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(
+                        seq,
+                        seq.wrapping_add(1),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                return seq;
+            }
+            backoff.snooze();
+        }
+    }
+}
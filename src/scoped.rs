@@ -0,0 +1,92 @@
+//! A thread-local stack of lazily published context references.
+//!
+//! [`set_current`] makes a [`LazyRef`] the current context of its type for
+//! the duration of a closure, and [`with_current`] reads the innermost
+//! current context of a type anywhere down the call stack, without threading
+//! it through every signature. The context is read from the cell on every
+//! access, so it can be published after being made current.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use lazy_ref::{scoped, LazyRef};
+//!
+//! struct Config {
+//!     verbose: bool,
+//! }
+//!
+//! fn deep_in_the_stack() -> Option<bool> {
+//!     scoped::with_current::<Config, _>(|config| config.map(|config| config.verbose))
+//! }
+//!
+//! let config = Config { verbose: true };
+//! let cell = LazyRef::new();
+//!
+//! assert_eq!(deep_in_the_stack(), None);
+//! scoped::set_current(&cell, || {
+//!     assert_eq!(deep_in_the_stack(), None);
+//!     cell.set(&config);
+//!     assert_eq!(deep_in_the_stack(), Some(true));
+//!
+//!     let quiet = LazyRef::new_initialized(&Config { verbose: false });
+//!     scoped::set_current(&quiet, || assert_eq!(deep_in_the_stack(), Some(false)));
+//!     assert_eq!(deep_in_the_stack(), Some(true));
+//! });
+//! assert_eq!(deep_in_the_stack(), None);
+//! ```
+
+use std::{
+    any::{Any, TypeId},
+    cell::RefCell,
+    thread_local,
+    vec::Vec,
+};
+
+use crate::LazyRef;
+
+thread_local! {
+    /// The current cells of the thread with the types of their references.
+    static STACK: RefCell<Vec<(TypeId, *const ())>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Pops the cells pushed since it was created when dropped, even on unwinding.
+struct Pop(usize);
+
+impl Drop for Pop {
+    #[inline]
+    fn drop(&mut self) {
+        STACK.with_borrow_mut(|stack| stack.truncate(self.0));
+    }
+}
+
+/// Calls `f` with `cell` as the current context of type `T` on the current
+/// thread.
+#[inline]
+pub fn set_current<T: Any, R>(cell: &LazyRef<'_, T>, f: impl FnOnce() -> R) -> R {
+    let len = STACK.with_borrow_mut(|stack| {
+        stack.push((TypeId::of::<T>(), core::ptr::from_ref(cell).cast()));
+        stack.len() - 1
+    });
+    let _pop = Pop(len);
+    f()
+}
+
+/// Calls `f` with the reference of the innermost current context of type
+/// `T` on the current thread.
+///
+/// `f` is given `None` if there is no current context of this type, or if it
+/// is not published yet.
+#[inline]
+pub fn with_current<T: Any, R>(f: impl FnOnce(Option<&T>) -> R) -> R {
+    let cell = STACK.with_borrow(|stack| {
+        stack
+            .iter()
+            .rev()
+            .find(|(type_id, _)| *type_id == TypeId::of::<T>())
+            .map(|(_, cell)| cell.cast::<LazyRef<'_, T>>())
+    });
+    // SAFETY:
+    // This is safe because the cell has been pushed as `LazyRef<'_, T>` by
+    // `set_current`, which pops it before the borrow of the cell ends.
+    f(cell.and_then(|cell| unsafe { &*cell }.get()))
+}
@@ -0,0 +1,298 @@
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    cell::RefCell,
+    fmt::{Debug, Formatter},
+};
+#[cfg(feature = "std")]
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use crate::{telemetry::SlowPath, Error, LazyRef, Snapshot};
+
+/// A thread-safe memoized function over the indices `0..len`.
+///
+/// Every index is computed with the stored function on the first access and
+/// kept in a [`LazyRef`] cell afterwards. Like [`LazyRef::get_or_init`], the
+/// function may be called multiple times for the same index when it is
+/// accessed from many threads concurrently.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyRefSeq;
+///
+/// let arena: Vec<u64> = (0..10).map(|n| n * n).collect();
+/// let squares = LazyRefSeq::new(arena.len(), |idx| &arena[idx]);
+///
+/// assert_eq!(squares.get(3), Some(&9));
+/// assert_eq!(squares.peek(3), Some(&9));
+/// assert_eq!(squares.peek(4), None);
+/// assert_eq!(squares.get(10), None);
+/// ```
+pub struct LazyRefSeq<'a, T, F> {
+    cells: Box<[LazyRef<'a, T>]>,
+    f: F,
+    /// The index to resume the warm-up from.
+    #[cfg(feature = "std")]
+    warm_from: AtomicUsize,
+}
+
+impl<T: Debug, F> Debug for LazyRefSeq<'_, T, F> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.cells.iter()).finish()
+    }
+}
+
+impl<'a, T, F> LazyRefSeq<'a, T, F> {
+    /// Gets the reference at `idx` without computing it.
+    ///
+    /// Returns `None` if `idx` is out of bounds or the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn peek(&self, idx: usize) -> Option<&'a T> {
+        self.cells.get(idx)?.get()
+    }
+
+    /// Returns the number of indices of the sequence.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Checks whether the sequence has no indices.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the underlying cells.
+    #[inline]
+    #[must_use]
+    pub fn cells(&self) -> &[LazyRef<'a, T>] {
+        &self.cells
+    }
+
+    /// Takes a point-in-time view of the computed indices, without computing
+    /// the rest.
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<'a, T> {
+        Snapshot::of(&self.cells)
+    }
+
+    /// Serializes a point-in-time snapshot of the computed indices as a map
+    /// from the indices to their references, without computing the rest.
+    ///
+    /// The signature matches `#[serde(serialize_with = "...")]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `serializer`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::LazyRefSeq;
+    ///
+    /// let arena = ["zero", "one", "two"];
+    /// let names = LazyRefSeq::new(arena.len(), |idx| &arena[idx]);
+    /// let _ = names.get(2);
+    ///
+    /// let mut json = Vec::new();
+    /// names
+    ///     .snapshot_serialize(&mut serde_json::Serializer::new(&mut json))
+    ///     .unwrap();
+    /// assert_eq!(json, br#"{"2":"two"}"#);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn snapshot_serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+    {
+        crate::snapshot::serialize_snapshot(&self.cells, serializer)
+    }
+}
+
+impl<'a, T, F: Fn(usize) -> &'a T> LazyRefSeq<'a, T, F> {
+    /// Creates a new sequence of `len` empty cells, initialized with `f` on
+    /// demand.
+    #[inline]
+    #[must_use]
+    pub fn new(len: usize, f: F) -> Self {
+        Self {
+            cells: (0..len).map(|_| LazyRef::new()).collect(),
+            f,
+            #[cfg(feature = "std")]
+            warm_from: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gets the reference at `idx`, computing it if the cell is empty.
+    ///
+    /// Returns `None` if `idx` is out of bounds.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<&'a T> {
+        let cell = self.cells.get(idx)?;
+        let mut slow = SlowPath::new("LazyRefSeq");
+        let r = cell.get_or_init(|| slow.compute(|| (self.f)(idx)));
+        Some(slow.finish(r))
+    }
+
+    /// Computes the empty cells in order for about `budget`, resuming where the
+    /// previous call stopped, and returns whether no cells are left to warm
+    /// up.
+    ///
+    /// At least one cell is computed per call, unless none are left. Cells
+    /// computed on demand in the meantime are skipped, and concurrent calls
+    /// share the remaining cells.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::time::Duration;
+    ///
+    /// use lazy_ref::LazyRefSeq;
+    ///
+    /// let arena: Vec<u64> = (0..1000).collect();
+    /// let cache = LazyRefSeq::new(arena.len(), |idx| &arena[idx]);
+    ///
+    /// // Once per frame.
+    /// while !cache.warm_for(Duration::from_micros(100)) {}
+    /// assert!(cache.cells().iter().all(|cell| cell.is_initialized()));
+    /// ```
+    #[cfg(feature = "std")]
+    #[inline]
+    pub fn warm_for(&self, budget: Duration) -> bool {
+        let deadline = Instant::now() + budget;
+        loop {
+            let Ok(idx) =
+                self.warm_from
+                    .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |idx| {
+                        (idx < self.len()).then_some(idx + 1)
+                    })
+            else {
+                return true;
+            };
+            let _ = self.get(idx);
+            if Instant::now() >= deadline {
+                return self.warm_from.load(Ordering::Relaxed) == self.len();
+            }
+        }
+    }
+}
+
+impl<'a, T, F> LazyRefSeq<'a, T, F>
+where
+    F: Fn(usize, &Resolver<'_, 'a, T>) -> Result<&'a T, Error>,
+{
+    /// Creates a new sequence of `len` empty cells, initialized on demand
+    /// with `f`, which can resolve the other indices through the given
+    /// [`Resolver`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::{Error, LazyRefSeq};
+    ///
+    /// let fib = LazyRefSeq::new_recursive(91, |idx, fib| {
+    ///     if idx < 2 {
+    ///         return Ok(Box::leak(Box::new(idx as u64)));
+    ///     }
+    ///     let sum = fib.get(idx - 1)? + fib.get(idx - 2)?;
+    ///     Ok(Box::leak(Box::new(sum)))
+    /// });
+    /// assert_eq!(fib.resolve(90), Ok(&2_880_067_194_370_816_120));
+    ///
+    /// let cyclic = LazyRefSeq::new_recursive(2, |idx, seq| seq.get(1 - idx));
+    /// assert_eq!(cyclic.resolve(0), Err::<&u64, _>(Error::CyclicIndex(0)));
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn new_recursive(len: usize, f: F) -> Self {
+        Self {
+            cells: (0..len).map(|_| LazyRef::new()).collect(),
+            f,
+            #[cfg(feature = "std")]
+            warm_from: AtomicUsize::new(0),
+        }
+    }
+
+    /// Gets the reference at `idx`, computing it and the indices it depends
+    /// on if their cells are empty.
+    ///
+    /// The indices are computed recursively on the stack of the current
+    /// thread, so resolving the earlier indices first bounds the depth of the
+    /// recursion.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the function, or [`Error::CyclicIndex`] if an
+    /// index depends on itself, leaving the cells of the failed indices
+    /// empty.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx`, or any index resolved by the function, is out of
+    /// bounds.
+    #[inline]
+    pub fn resolve(&self, idx: usize) -> Result<&'a T, Error> {
+        Resolver {
+            cells: &self.cells,
+            f: &self.f,
+            in_progress: RefCell::new(Vec::new()),
+        }
+        .get(idx)
+    }
+}
+
+/// Resolves the indices of a recursive [`LazyRefSeq`] for the function
+/// computing another index.
+pub struct Resolver<'s, 'a, T> {
+    cells: &'s [LazyRef<'a, T>],
+    f: &'s dyn Fn(usize, &Self) -> Result<&'a T, Error>,
+    /// The indices being computed, innermost last.
+    in_progress: RefCell<Vec<usize>>,
+}
+
+impl<T> Debug for Resolver<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Resolver")
+            .field("in_progress", &self.in_progress.borrow())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> Resolver<'_, 'a, T> {
+    /// Gets the reference at `idx`, computing it if the cell is empty.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the function, or [`Error::CyclicIndex`] if `idx`
+    /// is being computed already.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds.
+    #[inline]
+    pub fn get(&self, idx: usize) -> Result<&'a T, Error> {
+        let cell = &self.cells[idx];
+        if let Some(r) = cell.get() {
+            return Ok(r);
+        }
+        if self.in_progress.borrow().contains(&idx) {
+            return Err(Error::CyclicIndex(idx));
+        }
+        self.in_progress.borrow_mut().push(idx);
+        let computed = (self.f)(idx, self);
+        self.in_progress.borrow_mut().pop();
+        Ok(cell.publish_if_empty(computed?))
+    }
+}
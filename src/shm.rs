@@ -0,0 +1,212 @@
+use core::{
+    alloc::Layout,
+    fmt::{Debug, Formatter},
+    marker::PhantomData,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A non-blocking synchronization primitive (cell) for lazy-initialized
+/// immutable references into a memory segment shared between processes.
+///
+/// Processes may map the same segment at different addresses, so the cell
+/// stores the offset of the referenced value from the start of the segment
+/// instead of its address. Every access takes the address the segment is
+/// mapped at in the current process.
+///
+/// A zeroed `ShmLazyRef` is an empty cell, so the cells can be placed in
+/// zero-initialized shared memory without construction. All the processes
+/// sharing the cell must have the same pointer width.
+///
+/// # Examples
+///
+/// Moving a segment to another address:
+///
+/// ```rust
+/// use std::mem::MaybeUninit;
+/// use lazy_ref::ShmLazyRef;
+///
+/// #[repr(C)]
+/// struct Segment {
+///     cell: ShmLazyRef<u64>,
+///     value: u64,
+/// }
+///
+/// let first = Box::new(Segment { cell: ShmLazyRef::new(), value: 42 });
+/// let first_base = std::ptr::from_ref(&*first).cast::<u8>();
+/// // SAFETY: `value` lies in the segment starting at `first_base`.
+/// unsafe { first.cell.set(first_base, &first.value) };
+///
+/// let mut second = Box::new(MaybeUninit::<Segment>::uninit());
+/// // SAFETY: `Segment` can be copied bitwise.
+/// let second = unsafe {
+///     second.as_mut_ptr().copy_from_nonoverlapping(&*first, 1);
+///     second.assume_init()
+/// };
+/// let second_base = std::ptr::from_ref(&*second).cast::<u8>();
+/// // SAFETY: the cell holds the offset of `value` in the segment starting at `second_base`.
+/// let value = unsafe { second.cell.get(second_base) }.unwrap();
+/// assert_eq!(value, &42);
+/// assert!(std::ptr::eq(value, &second.value));
+/// ```
+///
+/// The cell has the same size, alignment and ABI as `usize`, holding zero
+/// while empty. This is a stability guarantee, checked with
+/// [`LAYOUT`](Self::LAYOUT).
+#[repr(transparent)]
+pub struct ShmLazyRef<T> {
+    /// The offset of the value plus one, or zero if the cell is empty.
+    offset: AtomicUsize,
+    _phantom: PhantomData<*const T>,
+}
+
+// SAFETY:
+// The cell only holds an offset, and sending it to another thread allows
+// getting shared references to `T` there, which requires `T: Sync`.
+unsafe impl<T: Sync> Send for ShmLazyRef<T> {}
+
+// SAFETY:
+// Shared access allows getting shared references to `T` from any thread, which
+// requires `T: Sync`.
+unsafe impl<T: Sync> Sync for ShmLazyRef<T> {}
+
+impl<T> Debug for ShmLazyRef<T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("ShmLazyRef");
+        match self.offset() {
+            Some(offset) => d.field(&offset),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for ShmLazyRef<T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+const _: () = {
+    assert!(ShmLazyRef::<u64>::LAYOUT.size() == size_of::<usize>());
+    assert!(ShmLazyRef::<u64>::LAYOUT.align() == align_of::<usize>());
+};
+
+impl<T> ShmLazyRef<T> {
+    /// The layout of the cell, the same as of `usize`.
+    pub const LAYOUT: Layout = Layout::new::<Self>();
+
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            offset: AtomicUsize::new(0),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Gets the offset of the referenced value from the start of the segment.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn offset(&self) -> Option<usize> {
+        self.offset.load(Ordering::Acquire).checked_sub(1)
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.offset.load(Ordering::Relaxed) != 0
+    }
+
+    /// Gets the underlying reference, resolving it against the segment mapped
+    /// at `base` in the current process.
+    ///
+    /// Returns `None` if the cell is empty.
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the start of the segment the cell was set with in any
+    /// process, and the segment must stay mapped for `'a`. The value at the
+    /// stored offset must stay valid and immutable for `'a`.
+    #[inline]
+    #[must_use]
+    pub unsafe fn get<'a>(&self, base: *const u8) -> Option<&'a T> {
+        let offset = self.offset()?;
+        // SAFETY:
+        // This is safe because the offset points to a valid value in the segment mapped
+        // at `base`, which is guaranteed by the caller.
+        Some(unsafe { &*base.add(offset).cast::<T>() })
+    }
+
+    /// Gets the underlying reference, resolving it against the segment mapped
+    /// at `base` in the current process, and initializes the cell with `f`
+    /// if it was empty.
+    ///
+    /// Many threads and processes may call `get_or_init` concurrently with
+    /// different initializing functions. In this case multiple functions can
+    /// be executed, but only the first published reference is kept and
+    /// returned to every caller.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`get`](Self::get) and [`set`](Self::set).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the reference returned by `f` points before `base`.
+    #[inline]
+    #[must_use]
+    pub unsafe fn get_or_init<'a>(&self, base: *const u8, f: impl FnOnce() -> &'a T) -> &'a T {
+        // SAFETY:
+        // This is safe because the caller upholds the safety contract of `get`.
+        if let Some(r) = unsafe { self.get(base) } {
+            return r;
+        }
+        let r = f();
+        let offset = offset_from(base, r);
+        match self
+            .offset
+            .compare_exchange(0, offset + 1, Ordering::Release, Ordering::Acquire)
+        {
+            Ok(_) => r,
+            // SAFETY:
+            // This is safe because the caller upholds the safety contract of `get`.
+            Err(stored) => unsafe { &*base.add(stored - 1).cast::<T>() },
+        }
+    }
+
+    /// Sets the contents of this cell to `r`, storing its offset from the
+    /// segment mapped at `base` in the current process.
+    ///
+    /// # Safety
+    ///
+    /// `r` must point into the segment mapped at `base` and stay valid and
+    /// immutable as long as any process may get it from the cell.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `r` points before `base`.
+    #[inline]
+    pub unsafe fn set(&self, base: *const u8, r: &T) {
+        self.offset
+            .store(offset_from(base, r) + 1, Ordering::Release);
+    }
+}
+
+/// Computes the offset of `r` from `base`.
+///
+/// # Panics
+///
+/// Panics if `r` points before `base`.
+#[inline]
+fn offset_from<T>(base: *const u8, r: &T) -> usize {
+    core::ptr::from_ref(r)
+        .addr()
+        .checked_sub(base.addr())
+        .expect("the reference points before the start of the segment")
+}
@@ -0,0 +1,167 @@
+use core::{
+    iter::FusedIterator,
+    slice::Iter,
+    sync::atomic::{fence, Ordering},
+};
+
+use crate::LazyRef;
+
+/// Extension methods for slices of [`LazyRef`] cells.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, LazyRefSliceExt};
+///
+/// let values: Vec<usize> = vec![10, 20, 30];
+/// let cells: Vec<LazyRef<'_, usize>> = (0..3).map(|_| LazyRef::new()).collect();
+///
+/// cells[1].set(&values[1]);
+/// assert_eq!(cells.first_initialized(), Some(&20));
+/// assert_eq!(cells.count_initialized(), 1);
+///
+/// cells.init_missing_with(|idx| &values[idx]);
+/// assert!(cells.iter_initialized().eq(&values));
+/// ```
+pub trait LazyRefSliceExt<'a, T: 'a> {
+    /// Gets the underlying reference of the first initialized cell.
+    ///
+    /// Returns `None` if all the cells are empty.
+    #[must_use]
+    fn first_initialized(&self) -> Option<&'a T>;
+
+    /// Counts the initialized cells.
+    #[must_use]
+    fn count_initialized(&self) -> usize;
+
+    /// Iterates over the underlying references of the initialized cells,
+    /// skipping the empty ones.
+    fn iter_initialized(&self) -> IterInitialized<'_, 'a, T>;
+
+    /// Initializes every empty cell with `f`, which is given the index of the
+    /// cell.
+    ///
+    /// Cells initialized concurrently by other threads are left as is.
+    fn init_missing_with(&self, f: impl FnMut(usize) -> &'a T);
+
+    /// Sets the contents of every cell to the reference of the same index in
+    /// `refs`, with a single release fence for all of them.
+    ///
+    /// This is cheaper than setting every cell with [`LazyRef::set`] on
+    /// weakly ordered architectures, and pairs with the single acquire fence
+    /// of [`snapshot`](crate::snapshot) and
+    /// [`snapshot_iter`](crate::snapshot_iter) on the read side. The cells
+    /// are published one by one, so concurrent readers may observe only
+    /// some of them set.
+    ///
+    /// Like [`LazyRef::set`], overwrites the references the cells may
+    /// already hold.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `refs` and the slice differ in length.
+    ///
+    /// With the `audit_overwrites` feature, also panics if any of the cells
+    /// is initialized, leaving all of them intact.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::{snapshot_iter, LazyRef, LazyRefSliceExt};
+    ///
+    /// let values: Vec<usize> = (0..100).collect();
+    /// let refs: Vec<&usize> = values.iter().collect();
+    /// let cells: Vec<LazyRef<'_, usize>> = values.iter().map(|_| LazyRef::new()).collect();
+    ///
+    /// cells.set_all(&refs);
+    /// assert!(snapshot_iter(&cells).eq(refs.into_iter().map(Some)));
+    /// ```
+    fn set_all(&self, refs: &[&'a T]);
+}
+
+impl<'a, T> LazyRefSliceExt<'a, T> for [LazyRef<'a, T>] {
+    #[inline]
+    fn first_initialized(&self) -> Option<&'a T> {
+        self.iter().find_map(LazyRef::get)
+    }
+
+    #[inline]
+    fn count_initialized(&self) -> usize {
+        self.iter()
+            .filter(|cell| cell.is_initialized_relaxed())
+            .count()
+    }
+
+    #[inline]
+    fn iter_initialized(&self) -> IterInitialized<'_, 'a, T> {
+        IterInitialized { iter: self.iter() }
+    }
+
+    #[inline]
+    fn init_missing_with(&self, mut f: impl FnMut(usize) -> &'a T) {
+        for (idx, cell) in self.iter().enumerate() {
+            let _ = cell.get_or_init(|| f(idx));
+        }
+    }
+
+    #[inline]
+    fn set_all(&self, refs: &[&'a T]) {
+        assert_eq!(
+            self.len(),
+            refs.len(),
+            "the number of the references differs from the number of the cells"
+        );
+        #[cfg(feature = "audit_overwrites")]
+        assert!(
+            !self.iter().any(LazyRef::is_initialized_relaxed),
+            "`LazyRefSliceExt::set_all` tried to overwrite an initialized cell"
+        );
+        fence(Ordering::Release);
+        for (cell, r) in self.iter().zip(refs) {
+            cell.ptr
+                .store(core::ptr::from_ref(*r).cast_mut(), Ordering::Relaxed);
+            cell.published();
+        }
+    }
+}
+
+/// An iterator over the underlying references of the initialized cells of a
+/// slice.
+///
+/// Created by [`LazyRefSliceExt::iter_initialized`].
+#[derive(Debug)]
+pub struct IterInitialized<'s, 'a, T> {
+    iter: Iter<'s, LazyRef<'a, T>>,
+}
+
+impl<T> Clone for IterInitialized<'_, '_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+        }
+    }
+}
+
+impl<'a, T> Iterator for IterInitialized<'_, 'a, T> {
+    type Item = &'a T;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find_map(LazyRef::get)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.iter.size_hint().1)
+    }
+}
+
+impl<T> DoubleEndedIterator for IterInitialized<'_, '_, T> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.by_ref().rev().find_map(LazyRef::get)
+    }
+}
+
+impl<T> FusedIterator for IterInitialized<'_, '_, T> {}
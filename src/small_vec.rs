@@ -0,0 +1,296 @@
+use alloc::vec::Vec;
+use core::{
+    fmt::{Debug, Formatter},
+    ops::Deref,
+};
+
+use crate::{LazyRef, Snapshot};
+
+/// A vector of [`LazyRef`] cells storing up to `INLINE` cells inline before
+/// spilling to the heap.
+///
+/// Cells are added through unique access, but can be initialized through
+/// shared access like any other cell. Dereferences to a slice of cells, so
+/// the [`LazyRefSliceExt`](crate::LazyRefSliceExt) methods are available.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRefSliceExt, SmallLazyRefVec};
+///
+/// let values = [1, 2, 3];
+/// let mut cells = SmallLazyRefVec::<'_, i32, 2>::new();
+///
+/// let first = cells.push();
+/// let second = cells.push();
+/// assert!(!cells.spilled());
+///
+/// cells[first].set(&values[0]);
+/// assert_eq!(cells[second].get_or_init(|| &values[1]), &2);
+///
+/// let _ = cells.push();
+/// assert!(cells.spilled());
+/// cells.init_missing_with(|idx| &values[idx]);
+/// assert!(cells.iter_initialized().eq(&values));
+/// ```
+pub struct SmallLazyRefVec<'a, T, const INLINE: usize> {
+    storage: Storage<'a, T, INLINE>,
+}
+
+/// The storage of a [`SmallLazyRefVec`].
+enum Storage<'a, T, const INLINE: usize> {
+    Inline {
+        /// The cells, of which only the first `len` are in use.
+        cells: [LazyRef<'a, T>; INLINE],
+        len: usize,
+    },
+    Heap(Vec<LazyRef<'a, T>>),
+}
+
+impl<T, const INLINE: usize> Clone for SmallLazyRefVec<'_, T, INLINE> {
+    #[inline]
+    fn clone(&self) -> Self {
+        let mut clone = Self::new();
+        for cell in self.iter() {
+            clone.push_cell(cell.clone());
+        }
+        clone
+    }
+}
+
+impl<T: Debug, const INLINE: usize> Debug for SmallLazyRefVec<'_, T, INLINE> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, const INLINE: usize> Default for SmallLazyRefVec<'_, T, INLINE> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T, const INLINE: usize> Deref for SmallLazyRefVec<'a, T, INLINE> {
+    type Target = [LazyRef<'a, T>];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'a, T, const INLINE: usize> SmallLazyRefVec<'a, T, INLINE> {
+    /// Creates a new empty vector.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            storage: Storage::Inline {
+                cells: LazyRef::new_uninit_array(),
+                len: 0,
+            },
+        }
+    }
+
+    /// Creates a new empty vector able to hold `capacity` cells without
+    /// reallocating, moving the cells to the heap up front if `capacity`
+    /// exceeds `INLINE`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::SmallLazyRefVec;
+    ///
+    /// let mut cells = SmallLazyRefVec::<'_, i32, 2>::with_capacity(8);
+    /// assert!(cells.spilled());
+    /// assert_eq!(cells.capacity(), 8);
+    ///
+    /// cells.push();
+    /// cells.shrink_to_fit();
+    /// assert!(!cells.spilled());
+    /// ```
+    #[inline]
+    #[must_use]
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut cells = Self::new();
+        cells.reserve_exact(capacity);
+        cells
+    }
+
+    /// Returns the number of cells the vector can hold without reallocating.
+    #[inline]
+    #[must_use]
+    pub fn capacity(&self) -> usize {
+        match &self.storage {
+            Storage::Inline { .. } => INLINE,
+            Storage::Heap(cells) => cells.capacity(),
+        }
+    }
+
+    /// Reserves the capacity for exactly `additional` more cells, moving the
+    /// cells to the heap if they no longer fit inline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the new capacity overflows `usize`.
+    #[inline]
+    pub fn reserve_exact(&mut self, additional: usize) {
+        match &mut self.storage {
+            Storage::Inline { len, .. } => {
+                let capacity = len.checked_add(additional).expect("capacity overflow");
+                if capacity > INLINE {
+                    self.spill(capacity);
+                }
+            }
+            Storage::Heap(cells) => cells.reserve_exact(additional),
+        }
+    }
+
+    /// Shrinks the capacity as much as possible, moving the cells back inline
+    /// if they fit.
+    #[inline]
+    pub fn shrink_to_fit(&mut self) {
+        let Storage::Heap(heap) = &mut self.storage else {
+            return;
+        };
+        if heap.len() > INLINE {
+            heap.shrink_to_fit();
+            return;
+        }
+        let mut cells = LazyRef::new_uninit_array();
+        let len = heap.len();
+        for (slot, cell) in cells.iter_mut().zip(heap.drain(..)) {
+            *slot = cell;
+        }
+        self.storage = Storage::Inline { cells, len };
+    }
+
+    /// Returns the cells as a slice.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[LazyRef<'a, T>] {
+        match &self.storage {
+            Storage::Inline { cells, len } => &cells[..*len],
+            Storage::Heap(cells) => cells,
+        }
+    }
+
+    /// Takes a point-in-time view of the cells.
+    #[inline]
+    #[must_use]
+    pub fn snapshot(&self) -> Snapshot<'a, T> {
+        Snapshot::of(self.as_slice())
+    }
+
+    /// Serializes a point-in-time snapshot of the initialized cells as a map
+    /// from their indices to their references.
+    ///
+    /// The signature matches `#[serde(serialize_with = "...")]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of `serializer`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use lazy_ref::SmallLazyRefVec;
+    ///
+    /// let value = 7;
+    /// let mut cells = SmallLazyRefVec::<'_, i32, 2>::new();
+    /// cells.push();
+    /// let idx = cells.push();
+    /// cells[idx].set(&value);
+    ///
+    /// let mut json = Vec::new();
+    /// cells
+    ///     .snapshot_serialize(&mut serde_json::Serializer::new(&mut json))
+    ///     .unwrap();
+    /// assert_eq!(json, br#"{"1":7}"#);
+    /// ```
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn snapshot_serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: serde::Serialize,
+    {
+        crate::snapshot::serialize_snapshot(self.as_slice(), serializer)
+    }
+
+    /// Checks whether the cells have been moved to the heap.
+    #[inline]
+    #[must_use]
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    /// Appends an empty cell, returning its index.
+    #[inline]
+    pub fn push(&mut self) -> usize {
+        self.push_cell(LazyRef::new())
+    }
+
+    /// Appends `cell`, returning its index.
+    #[inline]
+    pub fn push_cell(&mut self, cell: LazyRef<'a, T>) -> usize {
+        match &mut self.storage {
+            Storage::Inline { cells, len } if *len < INLINE => {
+                cells[*len] = cell;
+                *len += 1;
+                *len - 1
+            }
+            Storage::Inline { .. } => {
+                self.spill(2 * INLINE.max(1));
+                self.push_cell(cell)
+            }
+            Storage::Heap(cells) => {
+                cells.push(cell);
+                cells.len() - 1
+            }
+        }
+    }
+
+    /// Removes the last cell, returning it.
+    ///
+    /// Returns `None` if the vector is empty.
+    #[inline]
+    pub fn pop(&mut self) -> Option<LazyRef<'a, T>> {
+        match &mut self.storage {
+            Storage::Inline { len: 0, .. } => None,
+            Storage::Inline { cells, len } => {
+                *len -= 1;
+                Some(core::mem::take(&mut cells[*len]))
+            }
+            Storage::Heap(cells) => cells.pop(),
+        }
+    }
+
+    /// Moves the inline cells to a heap allocation of `capacity` cells.
+    fn spill(&mut self, capacity: usize) {
+        if let Storage::Inline { cells, len } = &mut self.storage {
+            let mut heap = Vec::with_capacity(capacity);
+            heap.extend(
+                core::mem::replace(cells, LazyRef::new_uninit_array())
+                    .into_iter()
+                    .take(*len),
+            );
+            self.storage = Storage::Heap(heap);
+        }
+    }
+
+    /// Removes all the cells, keeping the allocated storage.
+    #[inline]
+    pub fn clear(&mut self) {
+        match &mut self.storage {
+            Storage::Inline { cells, len } => {
+                for cell in &mut cells[..*len] {
+                    cell.reset_owned();
+                }
+                *len = 0;
+            }
+            Storage::Heap(cells) => cells.clear(),
+        }
+    }
+}
@@ -0,0 +1,270 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::{
+    iter::FusedIterator,
+    slice::Iter,
+    sync::atomic::{fence, Ordering},
+};
+
+use crate::LazyRef;
+
+/// The number of cells [`SnapshotIter`] loads before a single fence.
+const CHUNK: usize = 32;
+
+/// Takes a snapshot of the underlying references of `cells`, with `None` for
+/// the empty ones.
+///
+/// Every cell is loaded with a relaxed load, followed by a single acquire
+/// fence for all of them, which is cheaper than loading every cell with
+/// [`LazyRef::get`] on weakly ordered architectures. Cells initialized
+/// concurrently may or may not be observed.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{snapshot, LazyRef};
+///
+/// let value = 1;
+/// let cells = [LazyRef::new(), LazyRef::new_initialized(&value)];
+///
+/// assert_eq!(snapshot(&cells), [None, Some(&1)]);
+/// ```
+#[cfg(feature = "alloc")]
+#[must_use]
+pub fn snapshot<'a, T>(cells: &[LazyRef<'a, T>]) -> Vec<Option<&'a T>> {
+    let ptrs: Vec<*mut T> = cells
+        .iter()
+        .map(|cell| cell.ptr.load(Ordering::Relaxed))
+        .collect();
+    fence(Ordering::Acquire);
+    ptrs.into_iter()
+        // SAFETY:
+        // This is safe because this pointer can only be created from a valid reference,
+        // or it is null, and the fence synchronizes with the thread that published it.
+        .map(|ptr| unsafe { ptr.as_ref() })
+        .collect()
+}
+
+/// An immutable point-in-time view of the underlying references of a
+/// container of cells, with `None` for the empty ones.
+///
+/// The view is a copy of the references taken like by [`snapshot`], so it
+/// can be iterated any number of times with the same results while the
+/// container keeps initializing its cells.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyRefSeq;
+///
+/// let arena: Vec<u32> = (0..4).map(|n| n * 10).collect();
+/// let seq = LazyRefSeq::new(arena.len(), |idx| &arena[idx]);
+/// let _ = seq.get(1);
+///
+/// let view = seq.snapshot();
+/// let _ = seq.get(2);
+/// assert_eq!(view.get(1), Some(&10));
+/// assert_eq!(view.get(2), None);
+/// assert!(view.iter_initialized().eq([(1, &10)]));
+/// assert_eq!(view.len(), 4);
+/// ```
+#[cfg(feature = "alloc")]
+pub struct Snapshot<'a, T> {
+    refs: Vec<Option<&'a T>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<T> Clone for Snapshot<'_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            refs: self.refs.clone(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T: core::fmt::Debug> core::fmt::Debug for Snapshot<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(&self.refs).finish()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, T> Snapshot<'a, T> {
+    /// Takes a snapshot of `cells`.
+    #[inline]
+    #[must_use]
+    pub fn of(cells: &[LazyRef<'a, T>]) -> Self {
+        Self {
+            refs: snapshot(cells),
+        }
+    }
+
+    /// Gets the reference at `idx`.
+    ///
+    /// Returns `None` if `idx` is out of bounds or the cell was empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<&'a T> {
+        self.refs.get(idx).copied().flatten()
+    }
+
+    /// Returns the number of the cells in the view.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.refs.len()
+    }
+
+    /// Checks whether the view has no cells.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.refs.is_empty()
+    }
+
+    /// Returns the references of the view, with `None` for the empty cells.
+    #[inline]
+    #[must_use]
+    pub fn as_slice(&self) -> &[Option<&'a T>] {
+        &self.refs
+    }
+
+    /// Iterates over the indices and the references of the initialized
+    /// cells.
+    #[inline]
+    pub fn iter_initialized(&self) -> impl Iterator<Item = (usize, &'a T)> + '_ {
+        self.refs
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, r)| Some((idx, (*r)?)))
+    }
+}
+
+/// Serializes [`snapshot`] of `cells` as a map from the indices of the
+/// initialized cells to their references.
+#[cfg(feature = "serde")]
+pub(crate) fn serialize_snapshot<T, S>(
+    cells: &[LazyRef<'_, T>],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    T: serde::Serialize,
+    S: serde::Serializer,
+{
+    let snapshot = snapshot(cells);
+    serializer.collect_map(
+        snapshot
+            .into_iter()
+            .enumerate()
+            .filter_map(|(idx, r)| Some((idx, r?))),
+    )
+}
+
+/// Iterates over a snapshot of the underlying references of `cells`, with
+/// `None` for the empty ones, without allocating.
+///
+/// The same as [`snapshot`], but the cells are loaded in chunks, with an
+/// acquire fence per chunk.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{snapshot_iter, LazyRef};
+///
+/// let values: Vec<usize> = (0..100).collect();
+/// let cells: Vec<LazyRef<'_, usize>> = values.iter().map(LazyRef::new_initialized).collect();
+///
+/// assert!(snapshot_iter(&cells).eq(values.iter().map(Some)));
+/// ```
+#[inline]
+#[must_use]
+pub fn snapshot_iter<'s, 'a, T>(cells: &'s [LazyRef<'a, T>]) -> SnapshotIter<'s, 'a, T> {
+    SnapshotIter {
+        cells: cells.iter(),
+        chunk: [None; CHUNK],
+        pos: 0,
+        len: 0,
+    }
+}
+
+/// An iterator over a snapshot of the underlying references of a slice of
+/// cells.
+///
+/// Created by [`snapshot_iter`].
+#[derive(Debug)]
+pub struct SnapshotIter<'s, 'a, T> {
+    /// The cells that haven't been loaded yet.
+    cells: Iter<'s, LazyRef<'a, T>>,
+    /// The references of the last loaded chunk.
+    chunk: [Option<&'a T>; CHUNK],
+    /// The position of the next reference in the chunk.
+    pos: usize,
+    /// The number of references in the chunk.
+    len: usize,
+}
+
+impl<T> Clone for SnapshotIter<'_, '_, T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            cells: self.cells.clone(),
+            chunk: self.chunk,
+            pos: self.pos,
+            len: self.len,
+        }
+    }
+}
+
+impl<T> SnapshotIter<'_, '_, T> {
+    /// Loads the next chunk of cells.
+    #[cold]
+    fn refill(&mut self) {
+        let mut ptrs = [core::ptr::null_mut(); CHUNK];
+        let mut len = 0;
+        for (ptr, cell) in ptrs.iter_mut().zip(self.cells.by_ref()) {
+            *ptr = cell.ptr.load(Ordering::Relaxed);
+            len += 1;
+        }
+        if len != 0 {
+            fence(Ordering::Acquire);
+        }
+        for (r, ptr) in self.chunk.iter_mut().zip(&ptrs[..len]) {
+            // SAFETY:
+            // This is safe because this pointer can only be created from a valid reference,
+            // or it is null, and the fence synchronizes with the thread that published it.
+            *r = unsafe { ptr.as_ref() };
+        }
+        self.pos = 0;
+        self.len = len;
+    }
+}
+
+impl<'a, T> Iterator for SnapshotIter<'_, 'a, T> {
+    type Item = Option<&'a T>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos == self.len {
+            self.refill();
+            if self.len == 0 {
+                return None;
+            }
+        }
+        let r = self.chunk[self.pos];
+        self.pos += 1;
+        Some(r)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len - self.pos + self.cells.len();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for SnapshotIter<'_, '_, T> {}
+
+impl<T> FusedIterator for SnapshotIter<'_, '_, T> {}
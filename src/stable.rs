@@ -0,0 +1,89 @@
+use core::pin::Pin;
+
+/// A handle to a value that stays in place for `'a`, such as a reference, a
+/// pinned reference or a handle into an arena.
+///
+/// The handles can publish their values through the cells, whatever the
+/// wrapper they come in, by
+/// [`LazyRef::get_or_init_from`](crate::LazyRef::get_or_init_from) and
+/// [`MaybeInit::get_or_init_from`](crate::MaybeInit::get_or_init_from).
+///
+/// # Safety
+///
+/// [`into_ref`](Self::into_ref) must return a reference to the value the
+/// handle refers to, which must be neither moved nor mutated until `'a`
+/// ends.
+///
+/// The entry points of the crate only publish the returned reference, which
+/// is checked by the borrow checker like any other, so their soundness does
+/// not depend on the contract. It is there for the unsafe code generic over
+/// the handles, which may rely on the returned reference pointing to the
+/// value of the handle itself, e.g. to find the handle of a published
+/// reference by its address, rather than to a copy of it.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, StableRef};
+///
+/// /// A handle to a value leaked for the rest of the process.
+/// struct Leaked<T: 'static>(&'static T);
+///
+/// // SAFETY: leaked values are never freed, moved or mutated.
+/// unsafe impl<T> StableRef<'static> for Leaked<T> {
+///     type Target = T;
+///
+///     fn into_ref(self) -> &'static T {
+///         self.0
+///     }
+/// }
+///
+/// static NAME: LazyRef<'static, String> = LazyRef::new();
+///
+/// let name = NAME.get_or_init_from(|| Leaked(Box::leak(Box::new("lazy".to_string()))));
+/// assert_eq!(name, "lazy");
+///
+/// static FALLBACK: String = String::new();
+/// assert_eq!(NAME.get_or_init_from(|| std::pin::Pin::new(&FALLBACK)), "lazy");
+/// ```
+pub unsafe trait StableRef<'a> {
+    /// The type of the value.
+    type Target: 'a;
+
+    /// Converts the handle into a reference to the value.
+    fn into_ref(self) -> &'a Self::Target;
+}
+
+// SAFETY:
+// This is safe because the value is borrowed immutably for `'a`.
+unsafe impl<'a, T> StableRef<'a> for &'a T {
+    type Target = T;
+
+    #[inline]
+    fn into_ref(self) -> &'a T {
+        self
+    }
+}
+
+// SAFETY:
+// This is safe because the value is borrowed for `'a`, and the borrow is
+// downgraded to an immutable one.
+unsafe impl<'a, T> StableRef<'a> for &'a mut T {
+    type Target = T;
+
+    #[inline]
+    fn into_ref(self) -> &'a T {
+        self
+    }
+}
+
+// SAFETY:
+// This is safe because the pinned value is borrowed immutably for `'a`.
+unsafe impl<'a, T> StableRef<'a> for Pin<&'a T> {
+    type Target = T;
+
+    #[inline]
+    fn into_ref(self) -> &'a T {
+        self.get_ref()
+    }
+}
@@ -0,0 +1,176 @@
+use core::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use crate::{Backoff, LazyRef, SpinBackoff};
+
+/// A [`LazyRef`] counting the replacements of its reference, for readers
+/// practicing optimistic concurrency.
+///
+/// Every publication increments the stamp of the cell. A reader gets the
+/// reference together with the stamp it was published under through
+/// [`get_stamped`](Self::get_stamped), and later checks with
+/// [`validate`](Self::validate) whether it has been replaced since.
+///
+/// The stamp and the reference are kept consistent with a sequence lock:
+/// writers briefly spin while another writer publishes, and so do the
+/// readers of the stamp. [`get`](Self::get) never spins. Stamps wrap around
+/// after `u32::MAX / 2` publications.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::StampedLazyRef;
+///
+/// let (first, second) = (1, 2);
+/// let cell = StampedLazyRef::new();
+/// assert_eq!(cell.get_stamped(), None);
+///
+/// let _ = cell.get_or_init(|| &first);
+/// let (stamp, r) = cell.get_stamped().unwrap();
+/// assert_eq!(r, &1);
+/// assert!(cell.validate(stamp));
+///
+/// cell.set(&second);
+/// assert!(!cell.validate(stamp));
+/// ```
+pub struct StampedLazyRef<'a, T> {
+    inner: LazyRef<'a, T>,
+    /// Twice the number of publications, plus one while a publication is in
+    /// progress.
+    seq: AtomicU32,
+}
+
+impl<T: Debug> Debug for StampedLazyRef<'_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_tuple("StampedLazyRef");
+        match self.get_stamped() {
+            Some(v) => d.field(&v),
+            None => d.field(&format_args!("<uninit>")),
+        };
+        d.finish()
+    }
+}
+
+impl<T> Default for StampedLazyRef<'_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, T> StampedLazyRef<'a, T> {
+    /// Creates a new empty cell.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            inner: LazyRef::new(),
+            seq: AtomicU32::new(0),
+        }
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get(&self) -> Option<&'a T> {
+        self.inner.get()
+    }
+
+    /// Gets the underlying reference together with the stamp it was
+    /// published under.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    #[must_use]
+    pub fn get_stamped(&self) -> Option<(u32, &'a T)> {
+        let mut backoff = SpinBackoff::default();
+        loop {
+            let seq = self.seq.load(Ordering::Acquire);
+            if seq & 1 == 1 {
+                backoff.snooze();
+                continue;
+            }
+            // The acquire load keeps the following load of the sequence from being
+            // reordered before it.
+            let r = self.inner.ptr.load(Ordering::Acquire);
+            if self.seq.load(Ordering::Relaxed) == seq {
+                // SAFETY:
+                // This is safe because this pointer can only be created from a valid
+                // reference, or it is null.
+                return unsafe { r.as_ref() }.map(|r| (seq / 2, r));
+            }
+        }
+    }
+
+    /// Checks whether the reference published under `stamp` has not been
+    /// replaced since.
+    #[inline]
+    #[must_use]
+    pub fn validate(&self, stamp: u32) -> bool {
+        self.seq.load(Ordering::Acquire) == stamp.wrapping_mul(2)
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty.
+    ///
+    /// Behaves like [`LazyRef::get_or_init`].
+    #[inline]
+    #[must_use]
+    pub fn get_or_init(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        if let Some(r) = self.get() {
+            return r;
+        }
+        let r = f();
+        let seq = self.lock();
+        if let Some(current) = self.inner.get() {
+            self.seq.store(seq, Ordering::Release);
+            return current;
+        }
+        self.inner.force_set(r);
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+        r
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_initialized(&self) -> bool {
+        self.inner.is_initialized()
+    }
+
+    /// Sets the contents of this cell to `r`, incrementing the stamp.
+    #[inline]
+    pub fn set(&self, r: &'a T) {
+        let seq = self.lock();
+        self.inner.force_set(r);
+        self.seq.store(seq.wrapping_add(2), Ordering::Release);
+    }
+
+    /// Waits for other writers and marks a publication as in progress,
+    /// returning the sequence before it.
+    fn lock(&self) -> u32 {
+        let mut backoff = SpinBackoff::default();
+        loop {
+            let seq = self.seq.load(Ordering::Relaxed);
+            if seq & 1 == 0
+                && self
+                    .seq
+                    .compare_exchange_weak(
+                        seq,
+                        seq.wrapping_add(1),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                return seq;
+            }
+            backoff.snooze();
+        }
+    }
+}
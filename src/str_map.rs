@@ -0,0 +1,119 @@
+use std::{
+    borrow::ToOwned,
+    fmt::{Debug, Formatter},
+    hash::{BuildHasher, RandomState},
+    string::String,
+};
+
+use crate::{map::RawMap, ArenaAlloc};
+
+/// A non-blocking map from strings to lazily computed strings, storing both
+/// the keys and the values in an arena.
+///
+/// Looking up a key takes neither a lock nor an allocation. Computing a
+/// missing value moves an owned copy of the key and the value into the
+/// arena. Like [`LazyRef::get_or_init`](crate::LazyRef::get_or_init), the
+/// value may be computed multiple times for the same key when it is looked
+/// up from many threads concurrently, in which case the copies that lose the
+/// race stay in the arena unused.
+///
+/// The keys are hashed with `S`, which defaults to the hasher of
+/// [`HashMap`](std::collections::HashMap).
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{ArenaAlloc, LazyStrMap};
+///
+/// struct Leak;
+///
+/// impl<T> ArenaAlloc<T> for Leak {
+///     fn alloc(&self, value: T) -> &T {
+///         Box::leak(Box::new(value))
+///     }
+/// }
+///
+/// let arena = Leak;
+/// let mangled = LazyStrMap::new(&arena);
+///
+/// let name = String::from("main");
+/// let symbol = mangled.get_or_init(&name, |name| format!("_Z{}{name}", name.len()));
+/// drop(name);
+/// assert_eq!(symbol, "_Z4main");
+///
+/// assert_eq!(mangled.get_or_init("main", |_| unreachable!()), "_Z4main");
+/// assert_eq!(mangled.get("main"), Some("_Z4main"));
+/// assert_eq!(mangled.get("exit"), None);
+/// assert_eq!(mangled.len(), 1);
+/// ```
+pub struct LazyStrMap<'a, A, S = RandomState> {
+    map: RawMap<&'a str, &'a str, S>,
+    arena: &'a A,
+}
+
+impl<A, S> Debug for LazyStrMap<'_, A, S> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("LazyStrMap").field(&self.map).finish()
+    }
+}
+
+impl<'a, A> LazyStrMap<'a, A> {
+    /// Creates a new empty map storing the strings in `arena`.
+    #[inline]
+    #[must_use]
+    pub fn new(arena: &'a A) -> Self {
+        Self::with_hasher(arena, RandomState::new())
+    }
+}
+
+impl<'a, A, S> LazyStrMap<'a, A, S> {
+    /// Creates a new empty map storing the strings in `arena` and hashing the
+    /// keys with `hasher`.
+    #[inline]
+    #[must_use]
+    pub const fn with_hasher(arena: &'a A, hasher: S) -> Self {
+        Self {
+            map: RawMap::with_hasher(hasher),
+            arena,
+        }
+    }
+
+    /// Returns the number of the keys with computed values.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Checks whether no values have been computed.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<'a, A, S: BuildHasher> LazyStrMap<'a, A, S> {
+    /// Gets the value of `key` without computing it.
+    ///
+    /// Returns `None` if the value hasn't been computed.
+    #[inline]
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.map.get(key).map(|(_, value)| *value)
+    }
+}
+
+impl<'a, A: ArenaAlloc<String>, S: BuildHasher> LazyStrMap<'a, A, S> {
+    /// Gets the value of `key`, computing it with `f` if it is missing.
+    #[inline]
+    pub fn get_or_init(&self, key: &str, f: impl FnOnce(&str) -> String) -> &'a str {
+        if let Some(value) = self.get(key) {
+            return value;
+        }
+        let value = self.arena.alloc(f(key));
+        let key = self.arena.alloc(key.to_owned());
+        self.map.get_or_insert_with(key, |_| value).1
+    }
+}
@@ -0,0 +1,45 @@
+/// Builds an array of [`LazyRef`](crate::LazyRef) cells in a constant
+/// expression, initializing the cells given a reference and leaving the ones
+/// given `_` empty.
+///
+/// Suits the static tables mixing the entries known at compile time with the
+/// ones filled lazily, such as dispatch tables.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{lazy_ref_table, LazyRef};
+///
+/// fn add(a: u32, b: u32) -> u32 {
+///     a + b
+/// }
+///
+/// fn mul(a: u32, b: u32) -> u32 {
+///     a * b
+/// }
+///
+/// static ADD: fn(u32, u32) -> u32 = add;
+/// static OPS: [LazyRef<'static, fn(u32, u32) -> u32>; 3] = lazy_ref_table![&ADD, _, _];
+///
+/// static MUL: fn(u32, u32) -> u32 = mul;
+/// OPS[1].set(&MUL);
+///
+/// assert_eq!(OPS[0].get().map(|op| op(2, 3)), Some(5));
+/// assert_eq!(OPS[1].get().map(|op| op(2, 3)), Some(6));
+/// assert_eq!(OPS[2].get(), None);
+/// ```
+#[macro_export]
+macro_rules! lazy_ref_table {
+    (@entries [$($done:expr,)*]) => {
+        $crate::LazyRef::new_table([$($done,)*])
+    };
+    (@entries [$($done:expr,)*] _ $(, $($rest:tt)*)?) => {
+        $crate::lazy_ref_table!(@entries [$($done,)* ::core::option::Option::None,] $($($rest)*)?)
+    };
+    (@entries [$($done:expr,)*] $r:expr $(, $($rest:tt)*)?) => {
+        $crate::lazy_ref_table!(@entries [$($done,)* ::core::option::Option::Some($r),] $($($rest)*)?)
+    };
+    ($($entries:tt)*) => {
+        $crate::lazy_ref_table!(@entries [] $($entries)*)
+    };
+}
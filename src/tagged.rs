@@ -0,0 +1,182 @@
+use core::{
+    marker::PhantomData,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use crossbeam_utils::atomic::AtomicConsume;
+
+use crate::VarianceMarker;
+
+/// One of the two references a [`LazyTagged`] can hold.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Tagged<'a, L, R> {
+    Left(&'a L),
+    Right(&'a R),
+}
+
+impl<L, R> Clone for Tagged<'_, L, R> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<L, R> Copy for Tagged<'_, L, R> {}
+
+/// A lazy-initialized cell holding one of two references, distinguished by
+/// the lowest bit of the stored pointer.
+///
+/// Both referenced types must be aligned to at least two bytes, which is
+/// checked at compile time.
+pub(crate) struct LazyTagged<'a, L, R> {
+    /// The null pointer if the cell is empty. Otherwise, a pointer to `L` or,
+    /// if its lowest bit is set, a pointer to `R`.
+    ptr: AtomicPtr<u8>,
+    _phantom: PhantomData<(VarianceMarker<'a, L>, VarianceMarker<'a, R>)>,
+}
+
+impl<'a, L, R> LazyTagged<'a, L, R> {
+    /// The bit marking a pointer to `R`.
+    const TAG: usize = 1;
+
+    /// Creates a new empty cell.
+    #[inline]
+    pub(crate) const fn new() -> Self {
+        const {
+            assert!(
+                align_of::<L>() > Self::TAG && align_of::<R>() > Self::TAG,
+                "both referenced types must be aligned to at least two bytes"
+            );
+        };
+        Self {
+            ptr: AtomicPtr::new(core::ptr::null_mut()),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Creates a new initialized cell.
+    #[inline]
+    pub(crate) fn new_initialized(r: Tagged<'a, L, R>) -> Self {
+        let cell = Self::new();
+        cell.ptr.store(Self::encode(r), Ordering::Relaxed);
+        cell
+    }
+
+    /// Gets the underlying reference.
+    ///
+    /// Returns `None` if the cell is empty.
+    #[inline]
+    pub(crate) fn get(&self) -> Option<Tagged<'a, L, R>> {
+        // SAFETY:
+        // This is safe because the pointer can only be encoded from a reference, or it
+        // is null.
+        unsafe { Self::decode(self.ptr.load_consume()) }
+    }
+
+    /// Gets the underlying reference of the cell, initializing it with `f` if
+    /// the cell was empty, without overwriting a concurrently published one.
+    #[cfg(feature = "either")]
+    #[inline]
+    pub(crate) fn get_or_init(&self, f: impl FnOnce() -> Tagged<'a, L, R>) -> Tagged<'a, L, R> {
+        if let Some(r) = self.get() {
+            return r;
+        }
+        let r = f();
+        match self.ptr.compare_exchange(
+            core::ptr::null_mut(),
+            Self::encode(r),
+            Ordering::Release,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => r,
+            Err(ptr) => {
+                // SAFETY:
+                // This is safe because the pointer can only be encoded from a reference, or it
+                // is null.
+                let r = unsafe { Self::decode(ptr) };
+                r.expect("the exchange failed, so the cell is not empty")
+            }
+        }
+    }
+
+    /// Replaces the contents of the cell with `new` if they are still
+    /// `current`.
+    ///
+    /// Returns the actual contents of the cell if they are not `current`.
+    #[cfg(feature = "std")]
+    #[inline]
+    pub(crate) fn compare_exchange(
+        &self,
+        current: Option<Tagged<'a, L, R>>,
+        new: Tagged<'a, L, R>,
+    ) -> Result<(), Option<Tagged<'a, L, R>>> {
+        let current = current.map_or(core::ptr::null_mut(), Self::encode);
+        match self.ptr.compare_exchange(
+            current,
+            Self::encode(new),
+            Ordering::Release,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => Ok(()),
+            // SAFETY:
+            // This is safe because the pointer can only be encoded from a reference, or it
+            // is null.
+            Err(ptr) => Err(unsafe { Self::decode(ptr) }),
+        }
+    }
+
+    /// Consumes the cell, returning the wrapped reference.
+    #[inline]
+    pub(crate) fn into_inner(self) -> Option<Tagged<'a, L, R>> {
+        // SAFETY:
+        // This is safe because the pointer can only be encoded from a reference, or it
+        // is null.
+        unsafe { Self::decode(self.ptr.into_inner()) }
+    }
+
+    /// Checks whether the cell is initialized.
+    #[inline]
+    pub(crate) fn is_initialized(&self) -> bool {
+        !self.ptr.load(Ordering::Relaxed).is_null()
+    }
+
+    /// Sets the contents of the cell to `r`.
+    #[inline]
+    pub(crate) fn set(&self, r: Tagged<'a, L, R>) {
+        self.ptr.store(Self::encode(r), Ordering::Release);
+    }
+
+    /// Stores the variant of `r` in the lowest bit of its pointer.
+    #[inline]
+    fn encode(r: Tagged<'a, L, R>) -> *mut u8 {
+        match r {
+            Tagged::Left(l) => core::ptr::from_ref(l).cast_mut().cast(),
+            Tagged::Right(r) => core::ptr::from_ref(r)
+                .cast_mut()
+                .cast::<u8>()
+                .map_addr(|addr| addr | Self::TAG),
+        }
+    }
+
+    /// Restores the reference encoded into `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be null or returned by [`encode`](Self::encode).
+    #[inline]
+    unsafe fn decode(ptr: *mut u8) -> Option<Tagged<'a, L, R>> {
+        if ptr.is_null() {
+            return None;
+        }
+        // SAFETY:
+        // This is safe because the pointer has been encoded from a reference of the
+        // type given by its tag, which is guaranteed by the caller.
+        Some(unsafe {
+            if ptr.addr() & Self::TAG == 0 {
+                Tagged::Left(&*ptr.cast::<L>())
+            } else {
+                Tagged::Right(&*ptr.map_addr(|addr| addr & !Self::TAG).cast::<R>())
+            }
+        })
+    }
+}
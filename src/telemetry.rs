@@ -0,0 +1,61 @@
+use core::marker::PhantomData;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+/// The slow path of a container initializing an entry, reported through the
+/// `metrics` facade with the `metrics` feature, and a no-op otherwise.
+pub(crate) struct SlowPath<'a, T> {
+    #[cfg(feature = "metrics")]
+    container: &'static str,
+    #[cfg(feature = "metrics")]
+    computed: Option<&'a T>,
+    _phantom: PhantomData<&'a T>,
+}
+
+impl<'a, T> SlowPath<'a, T> {
+    /// Starts observing the slow path of `container`.
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub(crate) const fn new(container: &'static str) -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            container,
+            #[cfg(feature = "metrics")]
+            computed: None,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Computes an entry with `f`, recording the time it takes.
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(clippy::unused_self))]
+    pub(crate) fn compute(&mut self, f: impl FnOnce() -> &'a T) -> &'a T {
+        #[cfg(feature = "metrics")]
+        let start = Instant::now();
+        let r = f();
+        #[cfg(feature = "metrics")]
+        {
+            metrics::histogram!("lazy_ref_init_duration_seconds", "container" => self.container)
+                .record(start.elapsed());
+            self.computed = Some(r);
+        }
+        r
+    }
+
+    /// Reports whether the computed entry, if any, has been published as
+    /// `published`, and returns `published`.
+    #[inline]
+    #[cfg_attr(not(feature = "metrics"), allow(clippy::unused_self))]
+    pub(crate) fn finish(self, published: &'a T) -> &'a T {
+        #[cfg(feature = "metrics")]
+        if let Some(computed) = self.computed {
+            let name = if core::ptr::eq(computed, published) {
+                "lazy_ref_initialized_total"
+            } else {
+                "lazy_ref_init_races_total"
+            };
+            metrics::counter!(name, "container" => self.container).increment(1);
+        }
+        published
+    }
+}
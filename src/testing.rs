@@ -0,0 +1,3 @@
+//! Helpers for testing the synchronization guarantees of the cells.
+
+pub mod hb;
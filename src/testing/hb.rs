@@ -0,0 +1,129 @@
+//! Assertions of the happens-before relation established by publishing a
+//! reference.
+//!
+//! A publisher writes a [`Payload`] with relaxed stores and then publishes a
+//! reference to it, while readers check that once they observe the
+//! reference, they also observe the writes through it. Since the cells are
+//! read with consume ordering, this only extends to the data reached through
+//! the observed reference, not to the unrelated data written before the
+//! publication.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use lazy_ref::{testing::hb, LazyRef};
+//!
+//! let payload = hb::Payload::new();
+//! let cell = LazyRef::new();
+//!
+//! hb::assert_happens_before(&payload, 4, |payload| cell.set(payload), || cell.get());
+//! ```
+
+use std::{
+    fmt::{Debug, Formatter},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+/// The number of the words of a [`Payload`].
+const WORDS: usize = 4;
+
+/// Data written with relaxed stores before a publication, for the readers
+/// to check that they observe it.
+pub struct Payload {
+    words: [AtomicUsize; WORDS],
+}
+
+impl Debug for Payload {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Payload").field(&self.is_written()).finish()
+    }
+}
+
+impl Default for Payload {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Payload {
+    /// Creates a new unwritten payload.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            words: [const { AtomicUsize::new(0) }; WORDS],
+        }
+    }
+
+    /// Writes the payload with relaxed stores.
+    #[inline]
+    pub fn write(&self) {
+        for (value, word) in (1..).zip(&self.words) {
+            word.store(value, Ordering::Relaxed);
+        }
+    }
+
+    /// Checks with relaxed loads whether the whole payload is observed as
+    /// written.
+    #[inline]
+    #[must_use]
+    pub fn is_written(&self) -> bool {
+        (1..)
+            .zip(&self.words)
+            .all(|(value, word)| word.load(Ordering::Relaxed) == value)
+    }
+
+    /// Asserts with relaxed loads that the whole payload is observed as
+    /// written.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any write to the payload is not observed.
+    #[inline]
+    #[track_caller]
+    pub fn assert_written(&self) {
+        assert!(
+            self.is_written(),
+            "the payload written before the publication is not observed"
+        );
+    }
+}
+
+/// Races `readers` threads against a publisher, asserting that every reader
+/// observing the reference to `payload` also observes it written.
+///
+/// The publisher writes `payload` and then publishes it with `publish`,
+/// while the readers poll `observe` until it returns the reference. If
+/// `publish` never makes `observe` return the reference, this never returns.
+///
+/// # Panics
+///
+/// Panics if a reader observes the reference but not the writes, or if
+/// `publish` or `observe` panics.
+#[inline]
+pub fn assert_happens_before<'p>(
+    payload: &'p Payload,
+    readers: usize,
+    publish: impl FnOnce(&'p Payload) + Send,
+    observe: impl Fn() -> Option<&'p Payload> + Sync,
+) {
+    let observe = &observe;
+    thread::scope(|s| {
+        for _ in 0..readers {
+            s.spawn(move || loop {
+                if let Some(payload) = observe() {
+                    payload.assert_written();
+                    break;
+                }
+                thread::yield_now();
+            });
+        }
+        s.spawn(move || {
+            payload.write();
+            publish(payload);
+        });
+    });
+}
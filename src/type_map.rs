@@ -0,0 +1,114 @@
+use std::{
+    any::{Any, TypeId},
+    fmt::{Debug, Formatter},
+    hash::RandomState,
+};
+
+use crate::{any::Erased, map::RawMap};
+
+/// A thread-safe registry of lazily computed references, holding one
+/// reference per type.
+///
+/// Every type gets its own type-erased slot in a lock-free map, so that
+/// extensions of unrelated types can be registered concurrently. Like
+/// [`LazyRef::get_or_init`](crate::LazyRef::get_or_init), the initializer of
+/// a type may be called multiple times when it is initialized from many
+/// threads concurrently, but every caller gets the first published reference.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::LazyTypeMap;
+///
+/// struct RequestId(u64);
+/// struct User(&'static str);
+///
+/// let (id, user) = (RequestId(42), User("alice"));
+/// let extensions = LazyTypeMap::new();
+///
+/// assert_eq!(extensions.get_or_init(|| &id).0, 42);
+/// assert_eq!(extensions.get_or_init(|| &RequestId(0)).0, 42);
+/// assert!(extensions.get::<User>().is_none());
+///
+/// let _ = extensions.get_or_init(|| &user);
+/// assert_eq!(extensions.get::<User>().map(|user| user.0), Some("alice"));
+/// assert_eq!(extensions.len(), 2);
+/// ```
+pub struct LazyTypeMap<'a> {
+    slots: RawMap<TypeId, Erased<'a>, RandomState>,
+}
+
+impl Debug for LazyTypeMap<'_> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let mut d = f.debug_set();
+        self.slots.for_each(|type_id, _| {
+            d.entry(type_id);
+        });
+        d.finish()
+    }
+}
+
+impl Default for LazyTypeMap<'_> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> LazyTypeMap<'a> {
+    /// Creates a new empty map.
+    #[inline]
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            slots: RawMap::with_hasher(RandomState::new()),
+        }
+    }
+
+    /// Gets the reference of type `T`.
+    ///
+    /// Returns `None` if there is no reference of this type yet.
+    #[inline]
+    #[must_use]
+    pub fn get<T: Any>(&self) -> Option<&'a T> {
+        self.slots.get(&TypeId::of::<T>())?.1.downcast_ref()
+    }
+
+    /// Gets the reference of type `T`, initializing it with `f` if there was
+    /// none.
+    #[inline]
+    #[must_use]
+    pub fn get_or_init<T: Any + Send + Sync>(&self, f: impl FnOnce() -> &'a T) -> &'a T {
+        let erased = *self
+            .slots
+            .get_or_insert_with(TypeId::of::<T>(), |_| f() as Erased<'a>)
+            .1;
+        let ptr: *const (dyn Any + Send + Sync) = erased;
+        // SAFETY:
+        // This is safe because the reference stored under `TypeId::of::<T>()` can
+        // only be created from `&'a T`.
+        unsafe { &*ptr.cast::<T>() }
+    }
+
+    /// Checks whether there is a reference of type `T`.
+    #[inline]
+    #[must_use]
+    pub fn contains<T: Any>(&self) -> bool {
+        self.slots.get(&TypeId::of::<T>()).is_some()
+    }
+
+    /// Returns the number of the types with a reference.
+    #[inline]
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Checks whether there are no references.
+    #[inline]
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
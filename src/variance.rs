@@ -0,0 +1,74 @@
+/// Asserts at compile time that a type is covariant over a lifetime, i.e.
+/// that the type with a longer lifetime coerces to the one with a shorter
+/// lifetime.
+///
+/// The lifetime is named before `=>` and can be used in the type after it.
+///
+/// Invariance cannot be asserted by code that compiles, so the opposite
+/// property should be checked by a `compile_fail` test converting the type
+/// the same way.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{assert_covariant, IterInitialized};
+///
+/// struct Wrapper<'a>(&'a str);
+///
+/// assert_covariant!('a => Wrapper<'a>);
+/// assert_covariant!('s => IterInitialized<'s, 'static, u32>);
+/// ```
+///
+/// Cells are invariant over the lifetime of the references they hold:
+///
+/// ```compile_fail
+/// use lazy_ref::{assert_covariant, LazyRef};
+///
+/// assert_covariant!('a => LazyRef<'a, u32>);
+/// ```
+#[macro_export]
+macro_rules! assert_covariant {
+    ($lt:lifetime => $ty:ty) => {
+        const _: () = {
+            type Alias<$lt> = $ty;
+
+            #[allow(dead_code)]
+            fn assert_covariant<'long: 'short, 'short>(value: Alias<'long>) -> Alias<'short> {
+                value
+            }
+        };
+    };
+}
+
+/// Asserts at compile time that a type is contravariant over a lifetime, i.e.
+/// that the type with a shorter lifetime coerces to the one with a longer
+/// lifetime.
+///
+/// The lifetime is named before `=>` and can be used in the type after it.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::assert_contravariant;
+///
+/// assert_contravariant!('a => fn(&'a str));
+/// ```
+///
+/// ```compile_fail
+/// use lazy_ref::{assert_contravariant, LazyRef};
+///
+/// assert_contravariant!('a => LazyRef<'a, u32>);
+/// ```
+#[macro_export]
+macro_rules! assert_contravariant {
+    ($lt:lifetime => $ty:ty) => {
+        const _: () = {
+            type Alias<$lt> = $ty;
+
+            #[allow(dead_code)]
+            fn assert_contravariant<'long: 'short, 'short>(value: Alias<'short>) -> Alias<'long> {
+                value
+            }
+        };
+    };
+}
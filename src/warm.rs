@@ -0,0 +1,124 @@
+//! Helpers for warming up cells with [`tokio`] tasks.
+
+use std::{future::Future, panic::resume_unwind};
+
+use tokio::task::{block_in_place, spawn_blocking, AbortHandle, JoinSet};
+
+use crate::LazyRef;
+
+/// Spawns `init` onto `set`, publishing its output to `cell` once it
+/// completes, and returns the handle to abort the task.
+///
+/// If `cell` gets initialized before `init` completes, it is left as is.
+/// Awaiting all the tasks of `set` thus warms up all the cells spawned onto it
+/// in parallel.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{warm, LazyRef};
+/// use tokio::task::JoinSet;
+///
+/// static PRIMES: LazyRef<'static, Vec<u32>> = LazyRef::new();
+/// static NAMES: LazyRef<'static, Vec<&str>> = LazyRef::new();
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let mut set = JoinSet::new();
+///     warm::spawn_init(&mut set, &PRIMES, async { &*Box::leak(Box::new(vec![2, 3, 5])) });
+///     warm::spawn_init(&mut set, &NAMES, async { &*Box::leak(Box::new(vec!["two"])) });
+///     set.join_all().await;
+/// });
+///
+/// assert_eq!(PRIMES.get(), Some(&vec![2, 3, 5]));
+/// assert_eq!(NAMES.get(), Some(&vec!["two"]));
+/// ```
+#[inline]
+pub fn spawn_init<T: Sync>(
+    set: &mut JoinSet<()>,
+    cell: &'static LazyRef<'static, T>,
+    init: impl Future<Output = &'static T> + Send + 'static,
+) -> AbortHandle {
+    set.spawn(async move {
+        let r = init.await;
+        let _ = cell.get_or_init(|| r);
+    })
+}
+
+/// Gets the underlying reference of `cell`, initializing it with `f` run by
+/// [`block_in_place`] if the cell was empty.
+///
+/// Running a CPU-heavy `f` this way keeps it from starving the other tasks
+/// of the runtime. Behaves like [`LazyRef::get_or_init`] otherwise.
+///
+/// # Panics
+///
+/// Panics if called from a current-thread Tokio runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{warm, LazyRef};
+///
+/// let table: Vec<u64> = (0..1000).map(|x| x * x).collect();
+/// let cell = LazyRef::new();
+///
+/// let runtime = tokio::runtime::Runtime::new().unwrap();
+/// let squares = runtime.block_on(async {
+///     warm::get_or_init_blocking_in_place(&cell, || &table)
+/// });
+/// assert_eq!(squares[10], 100);
+/// ```
+#[inline]
+pub fn get_or_init_blocking_in_place<'a, T>(
+    cell: &LazyRef<'a, T>,
+    f: impl FnOnce() -> &'a T,
+) -> &'a T {
+    if let Some(r) = cell.get() {
+        return r;
+    }
+    cell.get_or_init(|| block_in_place(f))
+}
+
+/// Gets the underlying reference of `cell`, initializing it with `f` run by
+/// [`spawn_blocking`] if the cell was empty.
+///
+/// Unlike [`get_or_init_blocking_in_place`], this works with any Tokio
+/// runtime, but requires `f` to be `'static`. If `cell` gets initialized
+/// while `f` runs, the result of `f` is discarded.
+///
+/// # Panics
+///
+/// Panics if called outside of a Tokio runtime, or if `f` panics or is
+/// cancelled by a shutdown of the runtime.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{warm, LazyRef};
+///
+/// static SQUARES: LazyRef<'static, Vec<u64>> = LazyRef::new();
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let squares = runtime.block_on(warm::get_or_init_spawn_blocking(&SQUARES, || {
+///     Box::leak(Box::new((0..1000).map(|x| x * x).collect()))
+/// }));
+/// assert_eq!(squares[10], 100);
+/// ```
+#[inline]
+pub async fn get_or_init_spawn_blocking<T: Sync>(
+    cell: &'static LazyRef<'static, T>,
+    f: impl FnOnce() -> &'static T + Send + 'static,
+) -> &'static T {
+    if let Some(r) = cell.get() {
+        return r;
+    }
+    let r = spawn_blocking(f)
+        .await
+        .unwrap_or_else(|e| resume_unwind(e.into_panic()));
+    cell.get_or_init(|| r)
+}
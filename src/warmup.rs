@@ -0,0 +1,212 @@
+use std::{
+    fmt::{Debug, Formatter},
+    future::Future,
+    pin::Pin,
+    sync::{Condvar, Mutex, MutexGuard, PoisonError},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+    vec::Vec,
+};
+
+use crate::{Error, LazyRef};
+
+/// The default interval of rechecking the cells while waiting.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A set of [`LazyRef`] cells to wait for until all of them are initialized,
+/// e.g. before accepting traffic.
+///
+/// Since cells don't notify anyone when initialized, publishers should call
+/// [`notify`](Self::notify) after initializing a registered cell. Blocking
+/// waiters additionally recheck the cells periodically, so they don't miss a
+/// publication without a notification.
+///
+/// # Examples
+///
+/// ```rust
+/// use lazy_ref::{LazyRef, WarmupSet};
+///
+/// let tables = [vec![1, 2], vec![3]];
+/// let cells = [LazyRef::new(), LazyRef::new()];
+///
+/// let mut warmup = WarmupSet::new();
+/// cells.iter().for_each(|cell| warmup.register(cell));
+///
+/// std::thread::scope(|s| {
+///     for (cell, table) in cells.iter().zip(&tables) {
+///         let warmup = &warmup;
+///         s.spawn(move || {
+///             cell.set(table);
+///             warmup.notify();
+///         });
+///     }
+///     warmup.wait_all_initialized();
+///     assert_eq!(warmup.pending(), 0);
+/// });
+/// ```
+pub struct WarmupSet<'c, 'a, T> {
+    cells: Vec<&'c LazyRef<'a, T>>,
+    poll_interval: Duration,
+    /// The wakers of the pending asynchronous waiters.
+    wakers: Mutex<Vec<Waker>>,
+    notified: Condvar,
+}
+
+impl<T: Debug> Debug for WarmupSet<'_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WarmupSet")
+            .field("cells", &self.cells)
+            .field("poll_interval", &self.poll_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> Default for WarmupSet<'_, '_, T> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'c, 'a, T> WarmupSet<'c, 'a, T> {
+    /// Creates a new empty set.
+    #[inline]
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_poll_interval(DEFAULT_POLL_INTERVAL)
+    }
+
+    /// Creates a new empty set, whose blocking waiters recheck the cells
+    /// every `poll_interval`.
+    #[inline]
+    #[must_use]
+    pub const fn with_poll_interval(poll_interval: Duration) -> Self {
+        Self {
+            cells: Vec::new(),
+            poll_interval,
+            wakers: Mutex::new(Vec::new()),
+            notified: Condvar::new(),
+        }
+    }
+
+    /// Registers `cell` to be waited for.
+    #[inline]
+    pub fn register(&mut self, cell: &'c LazyRef<'a, T>) {
+        self.cells.push(cell);
+    }
+
+    /// Returns the number of registered cells that are not initialized yet.
+    #[inline]
+    #[must_use]
+    pub fn pending(&self) -> usize {
+        self.cells
+            .iter()
+            .filter(|cell| !cell.is_initialized())
+            .count()
+    }
+
+    /// Checks whether all the registered cells are initialized.
+    #[inline]
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        self.cells.iter().all(|cell| cell.is_initialized())
+    }
+
+    /// Wakes all the waiters to recheck the cells.
+    #[inline]
+    pub fn notify(&self) {
+        let wakers = core::mem::take(&mut *self.lock());
+        self.notified.notify_all();
+        wakers.into_iter().for_each(Waker::wake);
+    }
+
+    /// Blocks the current thread until all the registered cells are
+    /// initialized.
+    #[inline]
+    pub fn wait_all_initialized(&self) {
+        let mut guard = self.lock();
+        while !self.is_ready() {
+            guard = self
+                .notified
+                .wait_timeout(guard, self.poll_interval)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+    }
+
+    /// Blocks the current thread until all the registered cells are
+    /// initialized or `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Timeout`] if `timeout` elapses first.
+    #[inline]
+    pub fn wait_all_initialized_timeout(&self, timeout: Duration) -> Result<(), Error> {
+        let deadline = Instant::now() + timeout;
+        let mut guard = self.lock();
+        while !self.is_ready() {
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return Err(Error::Timeout);
+            };
+            guard = self
+                .notified
+                .wait_timeout(guard, left.min(self.poll_interval))
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        Ok(())
+    }
+
+    /// Returns a future resolving once all the registered cells are
+    /// initialized.
+    ///
+    /// The future only rechecks the cells when [`notify`](Self::notify) is
+    /// called.
+    #[inline]
+    pub fn wait_all_initialized_async(&self) -> WaitAllInitialized<'_, 'c, 'a, T> {
+        WaitAllInitialized { set: self }
+    }
+
+    /// Locks the wakers, ignoring poisoning.
+    #[inline]
+    fn lock(&self) -> MutexGuard<'_, Vec<Waker>> {
+        self.wakers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// A future resolving once all the cells of a [`WarmupSet`] are initialized.
+///
+/// Created by [`WarmupSet::wait_all_initialized_async`].
+#[must_use = "futures do nothing unless polled"]
+pub struct WaitAllInitialized<'s, 'c, 'a, T> {
+    set: &'s WarmupSet<'c, 'a, T>,
+}
+
+impl<T: Debug> Debug for WaitAllInitialized<'_, '_, '_, T> {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("WaitAllInitialized").field(self.set).finish()
+    }
+}
+
+impl<T> Future for WaitAllInitialized<'_, '_, '_, T> {
+    type Output = ();
+
+    #[inline]
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.set.is_ready() {
+            return Poll::Ready(());
+        }
+        let mut wakers = self.set.lock();
+        // Rechecking under the lock ensures that a notification sent in between is
+        // not missed.
+        if self.set.is_ready() {
+            return Poll::Ready(());
+        }
+        if !wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,18 @@
+//! Checks that the cells are invariant over the lifetime of the references
+//! they hold, which is required for their soundness.
+
+#![cfg(feature = "std")]
+
+#[test]
+fn variance() {
+    let t = trybuild::TestCases::new();
+    t.pass("tests/variance/pass/*.rs");
+    t.compile_fail("tests/variance/fail/*.rs");
+}
+
+#[cfg(feature = "either")]
+#[test]
+fn variance_either() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/variance/either/*.rs");
+}
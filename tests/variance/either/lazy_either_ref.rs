@@ -0,0 +1,11 @@
+use lazy_ref::LazyEitherRef;
+
+fn shorten<'long: 'short, 'short>(value: LazyEitherRef<'long, u8, u16>) -> LazyEitherRef<'short, u8, u16> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyEitherRef<'short, u8, u16>) -> LazyEitherRef<'long, u8, u16> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::BroadcastRef;
+
+fn shorten<'long: 'short, 'short>(value: BroadcastRef<'long, u32>) -> BroadcastRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: BroadcastRef<'short, u32>) -> BroadcastRef<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::BroadcastToken;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: BroadcastToken<'c, 'long, u32>) -> BroadcastToken<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: BroadcastToken<'c, 'short, u32>) -> BroadcastToken<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
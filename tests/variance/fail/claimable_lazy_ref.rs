@@ -0,0 +1,11 @@
+use lazy_ref::ClaimableLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: ClaimableLazyRef<'long, u32>) -> ClaimableLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: ClaimableLazyRef<'short, u32>) -> ClaimableLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
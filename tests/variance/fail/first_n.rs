@@ -0,0 +1,11 @@
+use lazy_ref::FirstN;
+
+fn shorten<'long: 'short, 'short>(value: FirstN<'long, u32, 2>) -> FirstN<'short, u32, 2> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: FirstN<'short, u32, 2>) -> FirstN<'long, u32, 2> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::FutexLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: FutexLazyRef<'long, u32>) -> FutexLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: FutexLazyRef<'short, u32>) -> FutexLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
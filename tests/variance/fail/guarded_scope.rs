@@ -0,0 +1,11 @@
+use lazy_ref::GuardedScope;
+
+fn shorten<'long: 'short, 'short>(value: GuardedScope<'long>) -> GuardedScope<'short> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: GuardedScope<'short>) -> GuardedScope<'long> {
+    value
+}
+
+fn main() {}
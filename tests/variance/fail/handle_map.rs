@@ -0,0 +1,11 @@
+use lazy_ref::HandleMap;
+
+fn shorten<'long: 'short, 'short>(value: HandleMap<'long, u32>) -> HandleMap<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: HandleMap<'short, u32>) -> HandleMap<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::HashConsing;
+
+fn shorten<'long: 'short, 'short>(value: HashConsing<'long, u32>) -> HashConsing<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: HashConsing<'short, u32>) -> HashConsing<'long, u32> {
+    value
+}
+
+fn main() {}
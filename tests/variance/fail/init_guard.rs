@@ -0,0 +1,11 @@
+use lazy_ref::InitGuard;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: InitGuard<'c, 'long, u32>) -> InitGuard<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: InitGuard<'c, 'short, u32>) -> InitGuard<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
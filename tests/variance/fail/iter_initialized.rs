@@ -0,0 +1,11 @@
+use lazy_ref::IterInitialized;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: IterInitialized<'c, 'long, u32>) -> IterInitialized<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: IterInitialized<'c, 'short, u32>) -> IterInitialized<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::KeyedLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: KeyedLazyRef<'long, u8, u32>) -> KeyedLazyRef<'short, u8, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: KeyedLazyRef<'short, u8, u32>) -> KeyedLazyRef<'long, u8, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::LazyAnyRef;
+
+fn shorten<'long: 'short, 'short>(value: LazyAnyRef<'long>) -> LazyAnyRef<'short> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyAnyRef<'short>) -> LazyAnyRef<'long> {
+    value
+}
+
+fn main() {}
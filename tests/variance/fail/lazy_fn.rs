@@ -0,0 +1,11 @@
+use lazy_ref::LazyFn;
+
+fn shorten<'long: 'short, 'short>(value: LazyFn<'long, u8, u32, fn(&u8) -> &'long u32>) -> LazyFn<'short, u8, u32, fn(&u8) -> &'short u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyFn<'short, u8, u32, fn(&u8) -> &'short u32>) -> LazyFn<'long, u8, u32, fn(&u8) -> &'long u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::lazy_graph::LazyGraph;
+
+fn shorten<'long: 'short, 'short>(value: LazyGraph<'long, u32>) -> LazyGraph<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyGraph<'short, u32>) -> LazyGraph<'long, u32> {
+    value
+}
+
+fn main() {}
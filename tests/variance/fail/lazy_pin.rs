@@ -0,0 +1,11 @@
+use lazy_ref::LazyPin;
+
+fn shorten<'long: 'short, 'short>(value: LazyPin<'long, u32>) -> LazyPin<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyPin<'short, u32>) -> LazyPin<'long, u32> {
+    value
+}
+
+fn main() {}
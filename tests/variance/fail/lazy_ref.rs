@@ -0,0 +1,11 @@
+use lazy_ref::LazyRef;
+
+fn shorten<'long: 'short, 'short>(value: LazyRef<'long, u32>) -> LazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyRef<'short, u32>) -> LazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::LazyRefCache;
+
+fn shorten<'long: 'short, 'short>(value: LazyRefCache<'long, u8, u32, 4>) -> LazyRefCache<'short, u8, u32, 4> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyRefCache<'short, u8, u32, 4>) -> LazyRefCache<'long, u8, u32, 4> {
+    value
+}
+
+fn main() {}
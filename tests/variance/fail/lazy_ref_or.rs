@@ -0,0 +1,11 @@
+use lazy_ref::LazyRefOr;
+
+fn shorten<'long: 'short, 'short>(value: LazyRefOr<'long, u32>) -> LazyRefOr<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyRefOr<'short, u32>) -> LazyRefOr<'long, u32> {
+    value
+}
+
+fn main() {}
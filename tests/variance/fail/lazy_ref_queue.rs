@@ -0,0 +1,11 @@
+use lazy_ref::LazyRefQueue;
+
+fn shorten<'long: 'short, 'short>(value: LazyRefQueue<'long, u32, 2>) -> LazyRefQueue<'short, u32, 2> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyRefQueue<'short, u32, 2>) -> LazyRefQueue<'long, u32, 2> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::LazyRefSeq;
+
+fn shorten<'long: 'short, 'short>(value: LazyRefSeq<'long, u32, fn(usize) -> &'long u32>) -> LazyRefSeq<'short, u32, fn(usize) -> &'long u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyRefSeq<'short, u32, fn(usize) -> &'long u32>) -> LazyRefSeq<'long, u32, fn(usize) -> &'long u32> {
+    value
+}
+
+fn main() {}
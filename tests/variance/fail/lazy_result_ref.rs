@@ -0,0 +1,11 @@
+use lazy_ref::LazyResultRef;
+
+fn shorten<'long: 'short, 'short>(value: LazyResultRef<'long, u32, u32>) -> LazyResultRef<'short, u32, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyResultRef<'short, u32, u32>) -> LazyResultRef<'long, u32, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::LazyStrMap;
+
+fn shorten<'long: 'short, 'short>(value: LazyStrMap<'long, ()>) -> LazyStrMap<'short, ()> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyStrMap<'short, ()>) -> LazyStrMap<'long, ()> {
+    value
+}
+
+fn main() {}
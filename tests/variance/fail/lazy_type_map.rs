@@ -0,0 +1,11 @@
+use lazy_ref::LazyTypeMap;
+
+fn shorten<'long: 'short, 'short>(value: LazyTypeMap<'long>) -> LazyTypeMap<'short> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: LazyTypeMap<'short>) -> LazyTypeMap<'long> {
+    value
+}
+
+fn main() {}
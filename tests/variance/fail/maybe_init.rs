@@ -0,0 +1,11 @@
+use lazy_ref::MaybeInit;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: MaybeInit<'c, 'long, u32>) -> MaybeInit<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: MaybeInit<'c, 'short, u32>) -> MaybeInit<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
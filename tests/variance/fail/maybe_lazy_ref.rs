@@ -0,0 +1,11 @@
+use lazy_ref::MaybeLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: MaybeLazyRef<'long, u32>) -> MaybeLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: MaybeLazyRef<'short, u32>) -> MaybeLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
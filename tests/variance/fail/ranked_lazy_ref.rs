@@ -0,0 +1,11 @@
+use lazy_ref::RankedLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: RankedLazyRef<'long, u32>) -> RankedLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: RankedLazyRef<'short, u32>) -> RankedLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::RefInterner;
+
+fn shorten<'long: 'short, 'short>(value: RefInterner<'long, str>) -> RefInterner<'short, str> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: RefInterner<'short, str>) -> RefInterner<'long, str> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::RefRing;
+
+fn shorten<'long: 'short, 'short>(value: RefRing<'long, u32, 2>) -> RefRing<'short, u32, 2> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: RefRing<'short, u32, 2>) -> RefRing<'long, u32, 2> {
+    value
+}
+
+fn main() {}
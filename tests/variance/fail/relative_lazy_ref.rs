@@ -0,0 +1,11 @@
+use lazy_ref::RelativeLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: RelativeLazyRef<'long, u32>) -> RelativeLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: RelativeLazyRef<'short, u32>) -> RelativeLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::Resolver;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: Resolver<'c, 'long, u32>) -> Resolver<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: Resolver<'c, 'short, u32>) -> Resolver<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
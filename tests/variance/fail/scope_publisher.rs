@@ -0,0 +1,11 @@
+use lazy_ref::ScopePublisher;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: ScopePublisher<'c, 'long, u32>) -> ScopePublisher<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: ScopePublisher<'c, 'short, u32>) -> ScopePublisher<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
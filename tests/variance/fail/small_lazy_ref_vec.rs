@@ -0,0 +1,11 @@
+use lazy_ref::SmallLazyRefVec;
+
+fn shorten<'long: 'short, 'short>(value: SmallLazyRefVec<'long, u32, 2>) -> SmallLazyRefVec<'short, u32, 2> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: SmallLazyRefVec<'short, u32, 2>) -> SmallLazyRefVec<'long, u32, 2> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::SnapshotIter;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: SnapshotIter<'c, 'long, u32>) -> SnapshotIter<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: SnapshotIter<'c, 'short, u32>) -> SnapshotIter<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::StampedLazyRef;
+
+fn shorten<'long: 'short, 'short>(value: StampedLazyRef<'long, u32>) -> StampedLazyRef<'short, u32> {
+    value
+}
+
+fn lengthen<'long: 'short, 'short>(value: StampedLazyRef<'short, u32>) -> StampedLazyRef<'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::VacantEntry;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: VacantEntry<'c, 'long, u32>) -> VacantEntry<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: VacantEntry<'c, 'short, u32>) -> VacantEntry<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use lazy_ref::WaitAllInitialized;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: WaitAllInitialized<'c, 'c, 'long, u32>) -> WaitAllInitialized<'c, 'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: WaitAllInitialized<'c, 'c, 'short, u32>) -> WaitAllInitialized<'c, 'c, 'long, u32> {
+    value
+}
+
+fn main() {}
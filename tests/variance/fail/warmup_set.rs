@@ -0,0 +1,11 @@
+use lazy_ref::WarmupSet;
+
+fn shorten<'c, 'long: 'short, 'short: 'c>(value: WarmupSet<'c, 'long, u32>) -> WarmupSet<'c, 'short, u32> {
+    value
+}
+
+fn lengthen<'c, 'long: 'short, 'short: 'c>(value: WarmupSet<'c, 'short, u32>) -> WarmupSet<'c, 'long, u32> {
+    value
+}
+
+fn main() {}
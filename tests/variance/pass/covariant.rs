@@ -0,0 +1,9 @@
+use lazy_ref::{assert_covariant, IterInitialized, Snapshot, SnapshotIter, TypedAnyRef};
+
+assert_covariant!('s => IterInitialized<'s, 'static, u32>);
+assert_covariant!('a => &'a lazy_ref::LazyRef<'static, u32>);
+assert_covariant!('s => SnapshotIter<'s, 'static, u32>);
+assert_covariant!('a => TypedAnyRef<'a, u32>);
+assert_covariant!('a => Snapshot<'a, u32>);
+
+fn main() {}